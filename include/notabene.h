@@ -0,0 +1,88 @@
+#ifndef NOTABENE_H
+#define NOTABENE_H
+
+/* This file is generated by cbindgen. Do not edit it. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// A point in the source document.
+typedef struct NbPoint {
+  // The line, starting at 1.
+  size_t line;
+  // The column, starting at 1.
+  size_t column;
+  // The byte offset, starting at 0.
+  size_t offset;
+} NbPoint;
+
+// A diagnostic.
+typedef struct NbDiagnostic {
+  // The rule code (e.g., `E100`).
+  char *code;
+  // The message.
+  char *message;
+  // Whether the diagnostic has a position. Diagnostics for the document as a whole do not.
+  bool has_position;
+  // The start of the diagnostic, or zeros if it has no position.
+  struct NbPoint start;
+  // The end of the diagnostic, or zeros if it has no position.
+  struct NbPoint end;
+} NbDiagnostic;
+
+// The result of [`nb_lint`].
+typedef struct NbDiagnostics {
+  // The diagnostics, sorted by position.
+  struct NbDiagnostic *items;
+  // The number of diagnostics.
+  size_t len;
+  // The diagnostics as a JSON array, in the format of `nb lint --output-format json`.
+  char *json;
+} NbDiagnostics;
+
+// Parse a changelog and return it as a JSON object.
+//
+// Free the result with [`nb_string_free`].
+//
+// # Safety
+//
+// `source` must be a valid pointer to a NUL-terminated string.
+char *nb_parse(const char *source);
+
+// Lint a changelog.
+//
+// `options` is a JSON object that may select or ignore rules, and set the layout, the current
+// date, and the column encoding, which defaults to `utf-8`. For example:
+// `{"select": ["E100"], "columnEncoding": "utf-16"}`. `options` may be `NULL`.
+//
+// Free the result with [`nb_diagnostics_free`].
+//
+// # Safety
+//
+// `source` must be a valid pointer to a NUL-terminated string. `options` must be `NULL` or a
+// valid pointer to a NUL-terminated string.
+struct NbDiagnostics *nb_lint(const char *source, const char *options);
+
+// Free the result of [`nb_lint`].
+//
+// # Safety
+//
+// `diagnostics` must be `NULL` or a pointer returned by [`nb_lint`] that has not been freed.
+void nb_diagnostics_free(struct NbDiagnostics *diagnostics);
+
+// Free a string returned by this library.
+//
+// # Safety
+//
+// `s` must be `NULL` or a string returned by this library that has not been freed.
+void nb_string_free(char *s);
+
+// Return the message of the last error on this thread, or `NULL`.
+//
+// The message is valid until the next call to this library on the same thread.
+const char *nb_last_error(void);
+
+#endif  /* NOTABENE_H */
@@ -0,0 +1,125 @@
+//! The implementation of the language bindings.
+use std::collections::HashSet;
+
+use serde::Deserialize;
+
+use crate::changelog::OwnedChangelog;
+use crate::date::Date;
+use crate::diagnostic::{Diagnostic, JsonDiagnostic};
+use crate::linter::Linter;
+use crate::parser::{Layout, Parser};
+use crate::rule::Rule;
+use crate::ruleset::RuleSet;
+use crate::span::{Encoding, Locator, Position};
+
+/// Options for linting a changelog.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "camelCase")]
+pub(crate) struct LintOptions {
+    select: Option<HashSet<Rule>>,
+    ignore: Option<HashSet<Rule>>,
+    layout: Option<Layout>,
+    today: Option<Date>,
+    pub column_encoding: Option<Encoding>,
+}
+
+/// Parse a changelog.
+pub(crate) fn parse_changelog(source: &str) -> OwnedChangelog {
+    Parser::new().parse(source).to_owned()
+}
+
+/// Lint a changelog and locate the diagnostics, with columns in the encoding from `options`, or
+/// `encoding` if unset.
+pub(crate) fn lint_changelog(
+    source: &str,
+    options: &LintOptions,
+    encoding: Encoding,
+) -> Vec<JsonDiagnostic> {
+    let select = options
+        .select
+        .clone()
+        .unwrap_or_else(|| HashSet::from(Rule::ALL));
+    let ignore = options.ignore.clone().unwrap_or_default();
+    let ruleset = RuleSet::new(select.difference(&ignore).copied().collect::<HashSet<_>>());
+    let mut parser = Parser::new();
+    if let Some(layout) = options.layout {
+        parser = parser.with_layout(layout);
+    }
+    let changelog = parser.parse(source);
+    let locator = Locator::new(source).with_encoding(options.column_encoding.unwrap_or(encoding));
+    let mut diagnostics: Vec<Diagnostic<Position>> = Linter::new(&ruleset)
+        .with_today(options.today)
+        .lint(&changelog)
+        .into_iter()
+        .map(|d| d.locate(&locator))
+        .collect();
+    diagnostics.sort_by_key(|d| d.location);
+    diagnostics
+        .iter()
+        .map(|d| JsonDiagnostic::new(d, source, &locator, None))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = "# Changelog
+
+## [Unreleased]
+
+### Foo
+
+* Café 😀 bar
+
+[unreleased]: https://example.org/
+";
+
+    #[test]
+    fn test_parse_changelog() {
+        let changelog = serde_json::to_value(parse_changelog(SOURCE)).unwrap();
+        assert_eq!(
+            changelog,
+            serde_json::json!({
+                "title": "Changelog",
+                "unreleased": {
+                    "url": "https://example.org/",
+                    "changes": [{"kind": "Foo", "items": ["Café 😀 bar"]}],
+                },
+                "releases": [],
+            })
+        );
+    }
+
+    #[test]
+    fn test_lint_changelog() {
+        let options: LintOptions = serde_json::from_str(r#"{"columnEncoding": "utf-8"}"#).unwrap();
+        let diagnostics = lint_changelog(SOURCE, &options, Encoding::Utf8);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, "E103");
+        assert_eq!(diagnostics[0].message, "Invalid change type `Foo`");
+        assert_eq!(diagnostics[0].position.unwrap().start.column, 5);
+
+        let options: LintOptions = serde_json::from_str(r#"{"ignore": ["E103"]}"#).unwrap();
+        assert!(lint_changelog(SOURCE, &options, Encoding::Utf8).is_empty());
+        assert!(serde_json::from_str::<LintOptions>(r#"{"foo": 1}"#).is_err());
+    }
+
+    #[test]
+    fn test_lint_changelog_encoding() {
+        let source = "# Changelog\n\n## [Unreleased]\n\n### Added\n\n* 😀 [x]\n\n[unreleased]: https://example.org/\n";
+        let columns: Vec<usize> = ["utf-8", "utf-16", "utf-32"]
+            .iter()
+            .map(|encoding| {
+                let options: LintOptions =
+                    serde_json::from_str(&format!(r#"{{"columnEncoding": "{}"}}"#, encoding))
+                        .unwrap();
+                let diagnostics = lint_changelog(source, &options, Encoding::Utf8);
+                diagnostics[0].position.unwrap().start.column
+            })
+            .collect();
+        assert_eq!(columns, vec![8, 6, 5]);
+        let diagnostics = lint_changelog(source, &LintOptions::default(), Encoding::Utf16);
+        assert_eq!(diagnostics[0].position.unwrap().start.column, 6);
+    }
+}
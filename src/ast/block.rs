@@ -11,6 +11,20 @@ pub enum Block {
     Heading(Heading),
     Paragraph(Literal),
     List(List),
+    /// Any other block element, such as a code block, block quote, or ordered list.
+    Other(Literal),
+}
+
+impl Block {
+    /// Return the span of the block.
+    pub fn span(&self) -> Span {
+        match self {
+            Self::Heading(Heading { span, .. })
+            | Self::Paragraph(Literal { span })
+            | Self::List(List { span, .. })
+            | Self::Other(Literal { span }) => *span,
+        }
+    }
 }
 
 /// An inline element.
@@ -59,6 +59,16 @@ impl<'a> Iterator for Parser<'a> {
                     };
                     return Some(Block::List(list));
                 }
+                md::Event::Start(
+                    md::Tag::BlockQuote(_)
+                    | md::Tag::CodeBlock(_)
+                    | md::Tag::List(Some(_))
+                    | md::Tag::Table(_),
+                ) => {
+                    skip_block(&mut self.inner);
+                    let lit = Literal::new(range);
+                    return Some(Block::Other(lit));
+                }
                 _ => {}
             }
         }
@@ -107,6 +117,21 @@ fn parse_items<'a>(parser: &mut EventSpanIterator<'a>) -> Vec<Literal> {
     items
 }
 
+/// Consume events until the end of the current block, including any nested blocks.
+fn skip_block<'a>(parser: &mut EventSpanIterator<'a>) {
+    let mut depth = 1;
+    for (event, _) in &mut *parser {
+        match event {
+            md::Event::Start(_) => depth += 1,
+            md::Event::End(_) => depth -= 1,
+            _ => {}
+        }
+        if depth == 0 {
+            break;
+        }
+    }
+}
+
 fn read_span_until<'a>(parser: &mut EventSpanIterator<'a>, until: md::TagEnd) -> Span {
     let mut span = Span::default();
     for (event, span_) in &mut *parser {
@@ -130,7 +155,7 @@ mod tests {
 
     use super::*;
 
-    fn parser(s: &str) -> Parser {
+    fn parser(s: &str) -> Parser<'_> {
         let parser = md::Parser::new(s).into_offset_iter();
         let inner = md::utils::TextMergeWithOffset::new(parser);
         Parser::new(Box::new(inner))
@@ -218,6 +243,22 @@ Ut enim ad minim veniam, quis nostrud exercitation ullamco laboris nisi ut aliqu
         ");
     }
 
+    #[test]
+    fn test_parse_other() {
+        snapshot!(
+            "
+```
+cargo install notabene
+```
+
+> Lorem ipsum dolor sit amet
+
+1. Consectetur adipiscing elit
+2. Sed do eiusmod tempor
+        "
+        );
+    }
+
     #[test]
     fn test_parse_list() {
         snapshot!(
@@ -0,0 +1,310 @@
+//! C API.
+//!
+//! Build the shared library with `cargo rustc --lib --release --crate-type cdylib --features capi`
+//! and include `include/notabene.h`.
+//!
+//! All strings are NUL-terminated and UTF-8 encoded. Functions that return a pointer return
+//! `NULL` on error; call [`nb_last_error`] for the reason. Free returned values with
+//! [`nb_string_free`] or [`nb_diagnostics_free`].
+//!
+//! ```c
+//! NbDiagnostics *diagnostics = nb_lint(source, "{\"ignore\": [\"E100\"]}");
+//! if (diagnostics == NULL) {
+//!     fprintf(stderr, "error: %s\n", nb_last_error());
+//!     return 2;
+//! }
+//! for (size_t i = 0; i < diagnostics->len; i++) {
+//!     NbDiagnostic d = diagnostics->items[i];
+//!     printf("%zu:%zu: %s %s\n", d.start.line, d.start.column, d.code, d.message);
+//! }
+//! nb_diagnostics_free(diagnostics);
+//! ```
+use std::cell::RefCell;
+use std::ffi::{CStr, CString, c_char};
+use std::ptr;
+
+use crate::api::{self, LintOptions};
+use crate::diagnostic::JsonDiagnostic;
+use crate::span::{Encoding, Point};
+
+/// A diagnostic.
+#[repr(C)]
+pub struct NbDiagnostic {
+    /// The rule code (e.g., `E100`).
+    pub code: *mut c_char,
+    /// The message.
+    pub message: *mut c_char,
+    /// Whether the diagnostic has a position. Diagnostics for the document as a whole do not.
+    pub has_position: bool,
+    /// The start of the diagnostic, or zeros if it has no position.
+    pub start: NbPoint,
+    /// The end of the diagnostic, or zeros if it has no position.
+    pub end: NbPoint,
+}
+
+/// A point in the source document.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct NbPoint {
+    /// The line, starting at 1.
+    pub line: usize,
+    /// The column, starting at 1.
+    pub column: usize,
+    /// The byte offset, starting at 0.
+    pub offset: usize,
+}
+
+/// The result of [`nb_lint`].
+#[repr(C)]
+pub struct NbDiagnostics {
+    /// The diagnostics, sorted by position.
+    pub items: *mut NbDiagnostic,
+    /// The number of diagnostics.
+    pub len: usize,
+    /// The diagnostics as a JSON array, in the format of `nb lint --output-format json`.
+    pub json: *mut c_char,
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// Parse a changelog and return it as a JSON object.
+///
+/// Free the result with [`nb_string_free`].
+///
+/// # Safety
+///
+/// `source` must be a valid pointer to a NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn nb_parse(source: *const c_char) -> *mut c_char {
+    let result = unsafe { to_str(source) }.and_then(|source| {
+        let changelog = api::parse_changelog(source);
+        serde_json::to_string(&changelog).map_err(|e| e.to_string())
+    });
+    match result {
+        Ok(json) => into_c_string(json),
+        Err(e) => fail(e),
+    }
+}
+
+/// Lint a changelog.
+///
+/// `options` is a JSON object that may select or ignore rules, and set the layout, the current
+/// date, and the column encoding, which defaults to `utf-8`. For example:
+/// `{"select": ["E100"], "columnEncoding": "utf-16"}`. `options` may be `NULL`.
+///
+/// Free the result with [`nb_diagnostics_free`].
+///
+/// # Safety
+///
+/// `source` must be a valid pointer to a NUL-terminated string. `options` must be `NULL` or a
+/// valid pointer to a NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn nb_lint(
+    source: *const c_char,
+    options: *const c_char,
+) -> *mut NbDiagnostics {
+    let result = unsafe { lint(source, options) };
+    match result {
+        Ok(diagnostics) => Box::into_raw(Box::new(diagnostics)),
+        Err(e) => fail(e),
+    }
+}
+
+/// Free the result of [`nb_lint`].
+///
+/// # Safety
+///
+/// `diagnostics` must be `NULL` or a pointer returned by [`nb_lint`] that has not been freed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn nb_diagnostics_free(diagnostics: *mut NbDiagnostics) {
+    if diagnostics.is_null() {
+        return;
+    }
+    let diagnostics = unsafe { Box::from_raw(diagnostics) };
+    let items = unsafe {
+        Box::from_raw(ptr::slice_from_raw_parts_mut(
+            diagnostics.items,
+            diagnostics.len,
+        ))
+    };
+    for item in items.iter() {
+        unsafe {
+            nb_string_free(item.code);
+            nb_string_free(item.message);
+        }
+    }
+    unsafe { nb_string_free(diagnostics.json) };
+}
+
+/// Free a string returned by this library.
+///
+/// # Safety
+///
+/// `s` must be `NULL` or a string returned by this library that has not been freed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn nb_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        drop(unsafe { CString::from_raw(s) });
+    }
+}
+
+/// Return the message of the last error on this thread, or `NULL`.
+///
+/// The message is valid until the next call to this library on the same thread.
+#[unsafe(no_mangle)]
+pub extern "C" fn nb_last_error() -> *const c_char {
+    LAST_ERROR.with_borrow(|error| error.as_ref().map_or(ptr::null(), |e| e.as_ptr()))
+}
+
+unsafe fn lint(source: *const c_char, options: *const c_char) -> Result<NbDiagnostics, String> {
+    let source = unsafe { to_str(source) }?;
+    let options: LintOptions = if options.is_null() {
+        LintOptions::default()
+    } else {
+        serde_json::from_str(unsafe { to_str(options) }?).map_err(|e| e.to_string())?
+    };
+    let diagnostics = api::lint_changelog(source, &options, Encoding::Utf8);
+    let json = serde_json::to_string(&diagnostics).map_err(|e| e.to_string())?;
+    let items: Box<[NbDiagnostic]> = diagnostics.into_iter().map(NbDiagnostic::new).collect();
+    let len = items.len();
+    Ok(NbDiagnostics {
+        items: Box::into_raw(items).cast(),
+        len,
+        json: into_c_string(json),
+    })
+}
+
+impl NbDiagnostic {
+    fn new(diagnostic: JsonDiagnostic) -> Self {
+        let position = diagnostic.position.unwrap_or_default();
+        Self {
+            code: into_c_string(diagnostic.code),
+            message: into_c_string(diagnostic.message),
+            has_position: diagnostic.position.is_some(),
+            start: position.start.into(),
+            end: position.end.into(),
+        }
+    }
+}
+
+impl From<Point> for NbPoint {
+    fn from(point: Point) -> Self {
+        Self {
+            line: point.line,
+            column: point.column,
+            offset: point.offset,
+        }
+    }
+}
+
+unsafe fn to_str<'a>(s: *const c_char) -> Result<&'a str, String> {
+    if s.is_null() {
+        return Err("null pointer".to_string());
+    }
+    unsafe { CStr::from_ptr(s) }
+        .to_str()
+        .map_err(|e| e.to_string())
+}
+
+fn into_c_string(s: String) -> *mut c_char {
+    // Rule messages and changelog text cannot contain NUL because the source is a C string.
+    CString::new(s).unwrap_or_default().into_raw()
+}
+
+/// Record an error and return `NULL`.
+fn fail<T>(message: String) -> *mut T {
+    LAST_ERROR.set(CString::new(message).ok());
+    ptr::null_mut()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::path::Path;
+
+    fn c(s: &str) -> CString {
+        CString::new(s).unwrap()
+    }
+
+    unsafe fn string(s: *const c_char) -> String {
+        unsafe { CStr::from_ptr(s) }.to_str().unwrap().to_string()
+    }
+
+    #[test]
+    fn test_parse() {
+        let source = c("# Changelog\n\n## [Unreleased]\n\n[unreleased]: https://example.org/\n");
+        unsafe {
+            let json = nb_parse(source.as_ptr());
+            assert_eq!(
+                string(json),
+                r#"{"title":"Changelog","unreleased":{"url":"https://example.org/","changes":[]},"releases":[]}"#
+            );
+            nb_string_free(json);
+        }
+    }
+
+    #[test]
+    fn test_lint() {
+        let source = c(
+            "# Changelog\n\n## [Unreleased]\n\n### Foo\n\n* Café\n\n[unreleased]: https://example.org/\n",
+        );
+        unsafe {
+            let diagnostics = nb_lint(source.as_ptr(), ptr::null());
+            assert!(!diagnostics.is_null());
+            let items = std::slice::from_raw_parts((*diagnostics).items, (*diagnostics).len);
+            assert_eq!(items.len(), 1);
+            assert_eq!(string(items[0].code), "E103");
+            assert_eq!(string(items[0].message), "Invalid change type `Foo`");
+            assert!(items[0].has_position);
+            assert_eq!(
+                (items[0].start, items[0].end),
+                (NbPoint::new(5, 5, 34), NbPoint::new(5, 8, 37))
+            );
+            assert!(string((*diagnostics).json).starts_with(r#"[{"code":"E103""#));
+            nb_diagnostics_free(diagnostics);
+
+            let diagnostics = nb_lint(source.as_ptr(), c(r#"{"ignore": ["E103"]}"#).as_ptr());
+            assert_eq!((*diagnostics).len, 0);
+            nb_diagnostics_free(diagnostics);
+        }
+    }
+
+    #[test]
+    fn test_error() {
+        unsafe {
+            assert!(nb_lint(ptr::null(), ptr::null()).is_null());
+            assert_eq!(string(nb_last_error()), "null pointer");
+            let source = c("");
+            assert!(nb_lint(source.as_ptr(), c("{\"foo\": 1}").as_ptr()).is_null());
+            assert!(string(nb_last_error()).starts_with("unknown field `foo`"));
+            nb_diagnostics_free(ptr::null_mut());
+            nb_string_free(ptr::null_mut());
+        }
+    }
+
+    #[test]
+    fn test_header() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+        let mut header = Vec::new();
+        cbindgen::generate(root).unwrap().write(&mut header);
+        let expected = std::fs::read_to_string(root.join("include/notabene.h")).unwrap();
+        assert_eq!(
+            String::from_utf8(header).unwrap(),
+            expected,
+            "include/notabene.h is out of date; run `cbindgen --output include/notabene.h`"
+        );
+    }
+
+    impl NbPoint {
+        fn new(line: usize, column: usize, offset: usize) -> Self {
+            Self {
+                line,
+                column,
+                offset,
+            }
+        }
+    }
+}
@@ -1,17 +1,23 @@
 //! Owned versions of changelog types.
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
 use super::traits;
 
+#[cfg_attr(feature = "serde", derive(Serialize))]
 pub struct OwnedChangelog {
     pub(crate) title: Option<String>,
     pub(crate) unreleased: Option<OwnedUnreleased>,
     pub(crate) releases: Vec<OwnedRelease>,
 }
 
+#[cfg_attr(feature = "serde", derive(Serialize))]
 pub struct OwnedUnreleased {
     pub(crate) url: Option<String>,
     pub(crate) changes: Vec<OwnedChanges>,
 }
 
+#[cfg_attr(feature = "serde", derive(Serialize))]
 pub struct OwnedRelease {
     pub(crate) version: String,
     pub(crate) url: Option<String>,
@@ -20,6 +26,7 @@ pub struct OwnedRelease {
     pub(crate) changes: Vec<OwnedChanges>,
 }
 
+#[cfg_attr(feature = "serde", derive(Serialize))]
 pub struct OwnedChanges {
     pub(crate) kind: String,
     pub(crate) items: Vec<String>,
@@ -11,10 +11,12 @@ use super::{owned, traits};
 
 type SpannedStr<'a> = Spanned<&'a str>;
 
-#[derive(Debug, Default, PartialEq)]
+#[derive(Debug, PartialEq)]
 #[cfg_attr(feature = "serde", derive(Serialize))]
 pub struct ParsedChangelog<'a> {
     pub(crate) source: &'a str,
+    /// The heading level of the title.
+    pub(crate) level: usize,
     pub(crate) title: Option<SpannedStr<'a>>,
     pub(crate) unreleased: Option<ParsedUnreleased<'a>>,
     pub(crate) releases: Vec<ParsedRelease<'a>>,
@@ -25,6 +27,7 @@ pub struct ParsedChangelog<'a> {
 #[cfg_attr(feature = "serde", derive(Serialize))]
 pub struct ParsedUnreleased<'a> {
     pub(crate) heading_span: Span,
+    pub(crate) link_span: Span,
     pub(crate) url: Option<String>,
     pub(crate) changes: Vec<ParsedChanges<'a>>,
 }
@@ -33,8 +36,10 @@ pub struct ParsedUnreleased<'a> {
 #[cfg_attr(feature = "serde", derive(Serialize))]
 pub struct ParsedRelease<'a> {
     pub(crate) heading_span: Span,
+    pub(crate) link_span: Span,
     pub(crate) version: SpannedStr<'a>,
     pub(crate) url: Option<String>,
+    pub(crate) separator: Option<SpannedStr<'a>>,
     pub(crate) date: Option<SpannedStr<'a>>,
     pub(crate) yanked: Option<SpannedStr<'a>>,
     pub(crate) changes: Vec<ParsedChanges<'a>>,
@@ -57,6 +62,21 @@ pub enum InvalidSpan {
     DuplicateUnreleased(Span),
     DuplicateTitle(Span),
     InvalidUnreleasedPosition(Span),
+    UnexpectedContent(Span),
+    MissingChangeType(Span),
+}
+
+impl<'a> Default for ParsedChangelog<'a> {
+    fn default() -> Self {
+        Self {
+            source: Default::default(),
+            level: 1,
+            title: Default::default(),
+            unreleased: Default::default(),
+            releases: Default::default(),
+            invalid_spans: Default::default(),
+        }
+    }
 }
 
 impl<'a> traits::Changelog for ParsedChangelog<'a> {
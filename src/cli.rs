@@ -3,12 +3,26 @@ use std::path::PathBuf;
 use clap::builder::{Styles, ValueParser};
 use clap::{Arg, ArgGroup, Command, value_parser};
 
+use crate::Date;
 use crate::rule::{RULES_BY_CODE, Rule};
+use crate::span::Encoding;
 
+mod baseline;
 mod commands;
+mod compare;
 mod config;
+mod diff;
 mod error;
+mod files;
+mod fragments;
+mod generate;
+mod git;
+mod query;
+mod release;
+mod render;
 mod report;
+mod server;
+mod stats;
 
 pub fn main() -> error::Result<()> {
     let matches = Command::new("nb")
@@ -19,7 +33,19 @@ pub fn main() -> error::Result<()> {
             Command::new("lint")
                 .alias("check")
                 .about("Lint a changelog")
-                .arg(Arg::new("FILE").value_parser(value_parser!(PathBuf)))
+                .arg(
+                    Arg::new("FILE")
+                        .help("Files, directories, or glob patterns to lint, or - to read standard input [default: CHANGELOG.md]")
+                        .value_parser(value_parser!(PathBuf))
+                        .num_args(0..),
+                )
+                .arg(
+                    Arg::new("stdin_filename")
+                        .long("stdin-filename")
+                        .value_name("NAME")
+                        .help("The file name to report for standard input")
+                        .value_parser(value_parser!(PathBuf)),
+                )
                 .arg(
                     Arg::new("config_file")
                         .long("config-file")
@@ -32,6 +58,49 @@ pub fn main() -> error::Result<()> {
                         .value_parser(ValueParser::new(parse_output_format))
                         .default_value("full"),
                 )
+                .arg(
+                    Arg::new("column_encoding")
+                        .long("column-encoding")
+                        .value_name("ENCODING")
+                        .help("The unit of column numbers (default: utf-8)")
+                        .value_parser(ValueParser::new(parse_column_encoding)),
+                )
+                .arg(
+                    Arg::new("git")
+                        .long("git")
+                        .help("Check releases against git tags")
+                        .action(clap::ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("since")
+                        .long("since")
+                        .value_name("REF")
+                        .help("Check that the unreleased section has changes if there are commits since REF (default: the latest release tag)")
+                        .num_args(0..=1)
+                        .require_equals(true)
+                        .default_missing_value(""),
+                )
+                .arg(
+                    Arg::new("diff")
+                        .long("diff")
+                        .value_name("REF")
+                        .help("Only report diagnostics on lines changed since REF, or in a unified diff read from standard input (-)"),
+                )
+                .arg(
+                    Arg::new("baseline")
+                        .long("baseline")
+                        .value_name("FILE")
+                        .help("Only report diagnostics that are not in the baseline FILE")
+                        .value_parser(value_parser!(PathBuf)),
+                )
+                .arg(
+                    Arg::new("write_baseline")
+                        .long("write-baseline")
+                        .value_name("FILE")
+                        .help("Write all diagnostics to the baseline FILE")
+                        .value_parser(value_parser!(PathBuf))
+                        .conflicts_with("baseline"),
+                )
                 .arg(
                     Arg::new("select")
                         .long("select")
@@ -45,6 +114,166 @@ pub fn main() -> error::Result<()> {
                         .value_delimiter(','),
                 ),
         )
+        .subcommand(
+            Command::new("init")
+                .about("Create a changelog")
+                .arg(
+                    Arg::new("FILE")
+                        .help("The file to write, or - for standard output")
+                        .value_parser(value_parser!(PathBuf))
+                        .default_value("CHANGELOG.md"),
+                )
+                .arg(
+                    Arg::new("url")
+                        .long("url")
+                        .value_name("URL")
+                        .help("The repository URL for links (default: the git remote or the repository in Cargo.toml)"),
+                )
+                .arg(
+                    Arg::new("tags")
+                        .long("tags")
+                        .help("Add a release for each git tag")
+                        .action(clap::ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("force")
+                        .long("force")
+                        .short('f')
+                        .help("Overwrite an existing file")
+                        .action(clap::ArgAction::SetTrue),
+                ),
+        )
+        .subcommand(
+            Command::new("generate")
+                .about("Generate a changelog from commits in Conventional Commits format")
+                .arg(
+                    Arg::new("from_git")
+                        .long("from-git")
+                        .value_name("RANGE")
+                        .help("The git revision range to read commits from (e.g., v1.0.0..HEAD)")
+                        .required(true),
+                )
+                .arg(
+                    Arg::new("url")
+                        .long("url")
+                        .value_name("URL")
+                        .help("The repository URL for links (default: the git remote or the repository in Cargo.toml)"),
+                )
+                .arg(
+                    Arg::new("tags")
+                        .long("tags")
+                        .help("Add a release for each git tag instead of adding all changes to the unreleased section")
+                        .action(clap::ArgAction::SetTrue),
+                ),
+        )
+        .subcommand(
+            Command::new("diff")
+                .about("Compare two versions of a changelog")
+                .arg(
+                    Arg::new("FILE")
+                        .help("The old and new changelogs, or the changelog to compare with --git")
+                        .value_parser(value_parser!(PathBuf))
+                        .num_args(0..=2),
+                )
+                .arg(
+                    Arg::new("git")
+                        .long("git")
+                        .value_name("REV")
+                        .help("Compare the changelog at a git revision with the working tree, or at both ends of a range (e.g., v1.0.0..HEAD)"),
+                )
+                .arg(
+                    Arg::new("output_format")
+                        .long("output-format")
+                        .value_parser(ValueParser::new(parse_data_format)),
+                ),
+        )
+        .subcommand(
+            Command::new("fragment")
+                .about("Manage changelog fragments in the changelog.d directory")
+                .subcommand_required(true)
+                .subcommand(
+                    Command::new("new")
+                        .about("Create a fragment")
+                        .arg(
+                            Arg::new("TYPE")
+                                .help("The change type (e.g., fixed)")
+                                .required(true),
+                        )
+                        .arg(
+                            Arg::new("name")
+                                .long("name")
+                                .value_name("NAME")
+                                .help("The file name without the type (default: the git branch)"),
+                        )
+                        .arg(
+                            Arg::new("message")
+                                .long("message")
+                                .short('m')
+                                .value_name("TEXT")
+                                .help("The description of the change"),
+                        ),
+                ),
+        )
+        .subcommand(
+            Command::new("query")
+                .about("Print data from a changelog")
+                .arg(
+                    Arg::new("QUERY")
+                        .help("The query (e.g., releases[0].version or releases | where yanked)")
+                        .required(true),
+                )
+                .arg(
+                    Arg::new("FILE")
+                        .help("The changelog, or - for standard input")
+                        .value_parser(value_parser!(PathBuf))
+                        .default_value("CHANGELOG.md"),
+                )
+                .arg(
+                    Arg::new("output_format")
+                        .long("output-format")
+                        .value_parser(ValueParser::new(parse_data_format)),
+                ),
+        )
+        .subcommand(
+            Command::new("release")
+                .about("Move the unreleased changes and fragments to a new release")
+                .arg(
+                    Arg::new("VERSION")
+                        .help("The version to release")
+                        .required(true),
+                )
+                .arg(
+                    Arg::new("FILE")
+                        .help("The changelog")
+                        .value_parser(value_parser!(PathBuf))
+                        .default_value("CHANGELOG.md"),
+                )
+                .arg(
+                    Arg::new("date")
+                        .long("date")
+                        .value_name("DATE")
+                        .help("The release date (default: today)")
+                        .value_parser(ValueParser::new(parse_date)),
+                ),
+        )
+        .subcommand(
+            Command::new("stats")
+                .about("Print statistics about releases")
+                .arg(
+                    Arg::new("FILE")
+                        .help("The changelog")
+                        .value_parser(value_parser!(PathBuf))
+                        .default_value("CHANGELOG.md"),
+                )
+                .arg(
+                    Arg::new("output_format")
+                        .long("output-format")
+                        .value_parser(ValueParser::new(parse_data_format)),
+                ),
+        )
+        .subcommand(
+            Command::new("server").about("Run a language server over standard input and output"),
+        )
         .subcommand(
             Command::new("rule")
                 .about("Explain a rule")
@@ -66,7 +295,15 @@ pub fn main() -> error::Result<()> {
         .get_matches();
     match matches.subcommand() {
         Some(("lint", submatches)) => commands::lint(submatches),
+        Some(("diff", submatches)) => commands::diff(submatches),
+        Some(("generate", submatches)) => commands::generate(submatches),
+        Some(("init", submatches)) => commands::init(submatches),
+        Some(("fragment", submatches)) => commands::fragment(submatches),
+        Some(("query", submatches)) => commands::query(submatches),
+        Some(("release", submatches)) => commands::release(submatches),
         Some(("rule", submatches)) => commands::rule(submatches),
+        Some(("stats", submatches)) => commands::stats(submatches),
+        Some(("server", _)) => server::server(),
         _ => unreachable!(),
     }
 }
@@ -85,6 +322,28 @@ fn parse_rule_code(code: &str) -> Result<Rule, String> {
         .copied()
 }
 
+fn parse_date(date: &str) -> Result<Date, String> {
+    date.parse()
+        .map_err(|_| "a date in YYYY-MM-DD format".to_string())
+}
+
+fn parse_column_encoding(encoding: &str) -> Result<Encoding, String> {
+    match encoding.to_lowercase().as_str() {
+        "utf-8" | "utf8" => Ok(Encoding::Utf8),
+        "utf-16" | "utf16" => Ok(Encoding::Utf16),
+        "utf-32" | "utf32" => Ok(Encoding::Utf32),
+        _ => Err("utf-8, utf-16, utf-32".to_string()),
+    }
+}
+
+fn parse_data_format(format: &str) -> Result<report::DataFormat, String> {
+    match format.to_lowercase().as_str() {
+        "text" => Ok(report::DataFormat::Text),
+        "json" => Ok(report::DataFormat::Json),
+        _ => Err("text, json".to_string()),
+    }
+}
+
 fn parse_output_format(format: &str) -> Result<report::Format, String> {
     use report::Format::*;
     match format.to_lowercase().as_str() {
@@ -0,0 +1,246 @@
+//! Record existing diagnostics so later runs only report new diagnostics.
+//!
+//! A baseline identifies diagnostics by file, rule code, the release section that contains them,
+//! and a fingerprint of their content, so they survive changes to line numbers.
+use std::collections::BTreeMap;
+use std::path::{Component, Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::changelog::parsed::ParsedChangelog;
+use crate::diagnostic::Diagnostic;
+use crate::span::Ranged;
+
+use super::error::{Error, Result};
+
+/// The section name of the unreleased section.
+const UNRELEASED: &str = "Unreleased";
+
+#[derive(Debug, Default, PartialEq, Deserialize, Serialize)]
+pub struct Baseline {
+    entries: Vec<Entry>,
+}
+
+#[derive(Debug, PartialEq, Deserialize, Serialize)]
+struct Entry {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    path: Option<String>,
+    rule: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    release: Option<String>,
+    fingerprint: String,
+    count: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct Key {
+    path: Option<String>,
+    rule: String,
+    release: Option<String>,
+    fingerprint: String,
+}
+
+impl Baseline {
+    /// Create a baseline from the diagnostics for a changelog.
+    pub fn new(diagnostics: &[Diagnostic], changelog: &ParsedChangelog) -> Self {
+        let mut counts: BTreeMap<Key, usize> = BTreeMap::new();
+        for diagnostic in diagnostics {
+            *counts.entry(Key::new(diagnostic, changelog)).or_default() += 1;
+        }
+        let entries = counts
+            .into_iter()
+            .map(|(key, count)| Entry {
+                path: key.path,
+                rule: key.rule,
+                release: key.release,
+                fingerprint: key.fingerprint,
+                count,
+            })
+            .collect();
+        Self { entries }
+    }
+
+    /// Add the entries of another baseline.
+    pub fn extend(&mut self, other: Baseline) {
+        self.entries.extend(other.entries);
+    }
+
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let parse =
+            || -> Result<Self> { Ok(serde_json::from_str(&std::fs::read_to_string(path)?)?) };
+        parse().map_err(|e| e.in_file(path))
+    }
+
+    pub fn to_file(&self, path: &Path) -> Result<()> {
+        let mut s = serde_json::to_string_pretty(self)?;
+        s.push('\n');
+        std::fs::write(path, s).map_err(|e| Error::from(e).in_file(path))
+    }
+
+    /// Return the diagnostics that are not in the baseline.
+    ///
+    /// If the baseline records `n` diagnostics with the same key, only the first `n` matching
+    /// diagnostics are removed.
+    pub fn filter(
+        &self,
+        diagnostics: Vec<Diagnostic>,
+        changelog: &ParsedChangelog,
+    ) -> Vec<Diagnostic> {
+        let mut counts: BTreeMap<Key, usize> = self
+            .entries
+            .iter()
+            .map(|entry| {
+                let key = Key {
+                    path: entry.path.clone(),
+                    rule: entry.rule.clone(),
+                    release: entry.release.clone(),
+                    fingerprint: entry.fingerprint.clone(),
+                };
+                (key, entry.count)
+            })
+            .collect();
+        diagnostics
+            .into_iter()
+            .filter(
+                |diagnostic| match counts.get_mut(&Key::new(diagnostic, changelog)) {
+                    Some(count) if *count > 0 => {
+                        *count -= 1;
+                        false
+                    }
+                    _ => true,
+                },
+            )
+            .collect()
+    }
+}
+
+impl Key {
+    fn new(diagnostic: &Diagnostic, changelog: &ParsedChangelog) -> Self {
+        let content = match diagnostic.location {
+            Some(span) => changelog.source.get(span.range()).unwrap_or_default(),
+            None => diagnostic.argument.as_deref().unwrap_or_default(),
+        };
+        // Ignore `./` so the key does not depend on how the file was specified.
+        let path = diagnostic.path.as_ref().map(|path| {
+            path.components()
+                .filter(|c| *c != Component::CurDir)
+                .collect::<PathBuf>()
+                .to_string_lossy()
+                .into_owned()
+        });
+        Self {
+            path,
+            rule: diagnostic.code().to_string(),
+            release: diagnostic
+                .location
+                .and_then(|span| release(changelog, span.start)),
+            fingerprint: format!("{:016x}", fnv1a(content.trim().as_bytes())),
+        }
+    }
+}
+
+/// Return the name of the section that contains `offset`.
+fn release(changelog: &ParsedChangelog, offset: usize) -> Option<String> {
+    let unreleased = changelog
+        .unreleased
+        .iter()
+        .map(|u| (u.heading_span.start, UNRELEASED));
+    let releases = changelog
+        .releases
+        .iter()
+        .map(|r| (r.heading_span.start, r.version.value));
+    unreleased
+        .chain(releases)
+        .filter(|(start, _)| *start <= offset)
+        .max_by_key(|(start, _)| *start)
+        .map(|(_, name)| name.to_string())
+}
+
+/// Return the 64-bit FNV-1a hash of `bytes`.
+///
+/// Unlike [`std::hash::DefaultHasher`], the hash is stable across Rust releases.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use insta::assert_yaml_snapshot;
+
+    use crate::linter::Linter;
+    use crate::parser::parse;
+
+    const SOURCE: &str = "# Changelog
+
+## [Unreleased]
+
+### Changed
+
+## [1.0.0] - 2025-01-01
+
+### Fixed
+
+### Added
+
+### Foo
+
+* Foo
+
+[unreleased]: https://example.org/
+[1.0.0]: https://example.org/
+";
+
+    #[test]
+    fn test_new() {
+        let changelog = parse(SOURCE);
+        let diagnostics = Linter::default().lint(&changelog);
+        assert_yaml_snapshot!(Baseline::new(&diagnostics, &changelog));
+    }
+
+    #[test]
+    fn test_filter() {
+        let changelog = parse(SOURCE);
+        let diagnostics = Linter::default().lint(&changelog);
+        let baseline = Baseline::new(&diagnostics, &changelog);
+        assert_eq!(baseline.filter(diagnostics, &changelog), vec![]);
+
+        // Move the release and add a violation.
+        let source = SOURCE.replace(
+            "## [1.0.0]",
+            "## [1.1.0] - 2025-02-01\n\n### Bar\n\n* Bar\n\n## [1.0.0]",
+        ) + "[1.1.0]: https://example.org/\n";
+        let changelog = parse(&source);
+        let diagnostics = Linter::default().lint(&changelog);
+        let diagnostics = baseline.filter(diagnostics, &changelog);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(&source[diagnostics[0].location.unwrap().range()], "Bar");
+    }
+
+    #[test]
+    fn test_path() {
+        let changelog = parse(SOURCE);
+        let diagnostics = Linter::default()
+            .with_filename(Some("./CHANGELOG.md"))
+            .lint(&changelog);
+        let baseline = Baseline::new(&diagnostics, &changelog);
+        assert_eq!(baseline.entries[0].path.as_deref(), Some("CHANGELOG.md"));
+        let diagnostics = Linter::default()
+            .with_filename(Some("CHANGELOG.md"))
+            .lint(&changelog);
+        assert_eq!(baseline.filter(diagnostics, &changelog), vec![]);
+        let diagnostics = Linter::default()
+            .with_filename(Some("CHANGES.md"))
+            .lint(&changelog);
+        assert_eq!(baseline.filter(diagnostics, &changelog).len(), 4);
+    }
+
+    #[test]
+    fn test_fnv1a() {
+        assert_eq!(fnv1a(b""), 0xcbf29ce484222325);
+        assert_eq!(fnv1a(b"a"), 0xaf63dc4c8601ec8c);
+    }
+}
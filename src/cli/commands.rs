@@ -1,27 +1,73 @@
 use std::collections::{HashMap, HashSet};
-use std::io::{self, Write};
-use std::path::PathBuf;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 use clap::ArgMatches;
 
+use crate::changelog::owned::{OwnedChangelog, OwnedChanges, OwnedRelease, OwnedUnreleased};
+use crate::changelog::parsed::ParsedChangelog;
+use crate::diagnostic::Diagnostic;
+use crate::linter::CHANGE_TYPES;
 use crate::rule::Rule;
 use crate::ruleset::RuleSet;
-use crate::span::Locator;
-use crate::{Linter, parse};
+use crate::span::{Encoding, Locator, Position, Span};
+use crate::{Commits, Date, Layout, Linter, Parser, Region, Tag};
 
-use super::config::{Config, Lint};
+use super::baseline::Baseline;
+use super::compare;
+use super::config::{Config, Git, Lint};
+use super::diff;
 use super::error::{Error, Result};
-use super::report::{Format, report};
+use super::files::find;
+use super::fragments::{self, Fragment};
+use super::generate;
+use super::git;
+use super::query;
+use super::release;
+use super::render;
+use super::report::{DataFormat, File, Format, report, summary};
+use super::stats;
+
+/// Options shared by all files in a lint run.
+struct LintOptions<'a> {
+    /// The configuration file given on the command line.
+    config_file: Option<&'a Config>,
+    /// The configuration given by command-line flags.
+    cli_config: &'a Config,
+    since: Option<&'a str>,
+    diff: Option<Diff>,
+    baseline: Option<&'a Baseline>,
+    write_baseline: bool,
+    /// The name of the changelog read from standard input.
+    stdin_filename: Option<&'a Path>,
+}
+
+/// Where to read changes from.
+enum Diff {
+    /// Diff against a git ref.
+    Base(String),
+    /// A unified diff read from standard input.
+    Patch(String),
+}
+
+/// The result of linting one file.
+struct Linted {
+    path: PathBuf,
+    content: String,
+    diagnostics: Vec<Diagnostic>,
+    baseline: Option<Baseline>,
+}
 
 pub fn lint(matches: &ArgMatches) -> Result<()> {
-    let mut config = Config::load(None).unwrap();
-    if let Some(path) = matches.get_one::<PathBuf>("config_file") {
-        config = config.merge(&Config::from_file(path).unwrap());
+    let config_file = matches
+        .get_one::<PathBuf>("config_file")
+        .map(|path| Config::from_file(path))
+        .transpose()?;
+    let paths: Vec<PathBuf> = match matches.get_many::<PathBuf>("FILE") {
+        Some(values) => values.cloned().collect(),
+        None => vec![PathBuf::from("CHANGELOG.md")],
     };
-    let path = matches
-        .get_one::<PathBuf>("FILE")
-        .unwrap_or(&PathBuf::from("CHANGELOG.md"))
-        .clone();
     let select: Option<HashSet<Rule>> = matches
         .get_many::<Rule>("select")
         .map(|values| values.copied().collect());
@@ -29,47 +75,828 @@ pub fn lint(matches: &ArgMatches) -> Result<()> {
         .get_many::<Rule>("ignore")
         .map(|values| values.copied().collect());
     let output_format = matches.get_one::<Format>("output_format").copied();
+    let column_encoding = matches.get_one::<Encoding>("column_encoding").copied();
     let cli_config = Config {
         lint: Lint {
             select,
             ignore,
             output_format,
+            column_encoding,
+            git: Git {
+                enabled: matches.get_flag("git").then_some(true),
+                ..Default::default()
+            },
+            ..Lint::empty()
         },
+        ..Config::empty()
     };
+    // Settings that apply to the whole run come from the configuration for the current directory.
+    let mut config = Config::discover(Path::new("."))?;
+    if let Some(config_file) = &config_file {
+        config = config.merge(config_file);
+    }
     config = config.merge(&cli_config);
-    let rules: HashSet<Rule> = config
-        .lint
-        .select
-        .unwrap()
-        .difference(&config.lint.ignore.unwrap())
-        .copied()
+    let diff = match matches.get_one::<String>("diff").map(String::as_str) {
+        Some("-") if paths.iter().any(|path| is_stdin(path)) => {
+            return Err(Error::Usage(
+                "cannot read both the changelog and the diff from standard input".to_string(),
+            ));
+        }
+        Some("-") => {
+            let mut patch = String::new();
+            io::stdin().read_to_string(&mut patch)?;
+            Some(Diff::Patch(patch))
+        }
+        Some(base) => Some(Diff::Base(base.to_string())),
+        None => None,
+    };
+    let baseline = matches
+        .get_one::<PathBuf>("baseline")
+        .map(|path| Baseline::from_file(path))
+        .transpose()?;
+    let options = LintOptions {
+        config_file: config_file.as_ref(),
+        cli_config: &cli_config,
+        since: matches.get_one::<String>("since").map(String::as_str),
+        diff,
+        baseline: baseline.as_ref(),
+        write_baseline: matches.contains_id("write_baseline"),
+        stdin_filename: matches
+            .get_one::<PathBuf>("stdin_filename")
+            .map(PathBuf::as_path),
+    };
+    let mut files = Vec::new();
+    for path in paths {
+        if is_stdin(&path) {
+            files.push((path, None));
+            continue;
+        }
+        match split_section(&path) {
+            (path, Some(section)) => files.push((path, Some(section))),
+            (path, None) => files.extend(find(&[path])?.into_iter().map(|p| (p, None))),
+        }
+    }
+    // Report errors in a file and lint the other files, but exit with the error status.
+    let mut results = parallel_map(&files, |(path, section)| {
+        lint_file(path, section.as_deref(), &options).map_err(|error| match error {
+            Error::File(..) | Error::SectionNotFound(_) => error,
+            error => error.in_file(path),
+        })
+    });
+    // Lint the fragments in each directory once, even if it contains several changelogs.
+    let mut dirs = Vec::new();
+    let mut seen = HashSet::new();
+    for (path, _) in files.iter().filter(|(path, _)| !is_stdin(path)) {
+        let dir = repository_dir(path);
+        if seen.insert(dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf())) {
+            dirs.push(dir);
+        }
+    }
+    let mut fragment_paths = Vec::new();
+    for dir in dirs {
+        match fragments::find(dir) {
+            Ok(paths) => fragment_paths.extend(paths),
+            Err(error) => results.push(Err(error.in_file(dir))),
+        }
+    }
+    results.extend(parallel_map(&fragment_paths, |path| {
+        lint_fragment(path, &options).map_err(|error| match error {
+            Error::File(..) => error,
+            error => error.in_file(path),
+        })
+    }));
+    let mut linted = Vec::new();
+    let mut errors = 0;
+    for result in results {
+        match result {
+            Ok(file) => linted.push(file),
+            Err(error) => {
+                eprintln!("error: {}", error);
+                errors += 1;
+            }
+        }
+    }
+    let failed = if errors > 0 {
+        Error::Failed(errors)
+    } else {
+        Error::Check
+    };
+    if let Some(baseline_path) = matches.get_one::<PathBuf>("write_baseline") {
+        // A baseline without the files with errors would hide their diagnostics later.
+        if errors > 0 {
+            return Err(failed);
+        }
+        let mut baseline = Baseline::default();
+        for file in linted {
+            baseline.extend(file.baseline.unwrap_or_default());
+        }
+        return baseline.to_file(baseline_path);
+    }
+    if linted.iter().all(|file| file.diagnostics.is_empty()) {
+        return if errors > 0 { Err(failed) } else { Ok(()) };
+    }
+    let located: Vec<Vec<Diagnostic<Position>>> = linted
+        .iter()
+        .map(|file| {
+            let locator = Locator::new(&file.content);
+            let mut diagnostics: Vec<_> = file
+                .diagnostics
+                .iter()
+                .cloned()
+                .map(|d| d.locate(&locator))
+                .collect();
+            diagnostics.sort_by_key(|d| d.location);
+            diagnostics
+        })
         .collect();
-    let ruleset = RuleSet::new(rules);
-    let content = std::fs::read_to_string(&path)?;
-    let ir = parse(&content);
-    let linter = Linter::new(&ruleset).with_filename(Some(&path));
-    let diagnostics = linter.lint(&ir);
-    if diagnostics.is_empty() {
-        Ok(())
+    let files: Vec<File> = linted
+        .iter()
+        .zip(&located)
+        .map(|(file, diagnostics)| File {
+            source: &file.content,
+            path: Some(&file.path),
+            diagnostics,
+        })
+        .collect();
+    let mut output = io::stdout();
+    // TODO: Build final config.
+    let format = config.lint.output_format.unwrap();
+    let encoding = config.lint.column_encoding.unwrap_or_default();
+    report(&mut output, &files, format, encoding)?;
+    if format == Format::Full {
+        summary(&mut output, &files)?;
+    }
+    Err(failed)
+}
+
+/// Lint a single file.
+fn lint_file(path: &Path, section: Option<&str>, options: &LintOptions) -> Result<Linted> {
+    let from_stdin = is_stdin(path);
+    let (path, section, content) = if from_stdin {
+        let mut content = String::new();
+        io::stdin().read_to_string(&mut content)?;
+        match options.stdin_filename.map(split_section) {
+            Some((path, section)) => (path, section, content),
+            None => (path.to_path_buf(), None, content),
+        }
     } else {
-        let locator = Locator::new(&content);
-        let mut diagnostics: Vec<_> = diagnostics
+        let content = std::fs::read_to_string(path).map_err(|e| Error::from(e).in_file(path))?;
+        (path.to_path_buf(), section.map(str::to_string), content)
+    };
+    let path = path.as_path();
+    let config = file_config(path, options)?;
+    let ruleset = RuleSet::new(config.lint.rules(path)?);
+    let ir = parse_changelog(path, &content, section.clone(), &config)?;
+    let tags = if config.lint.git.enabled.unwrap_or_default() {
+        let pattern = config.lint.git.tag_pattern.as_deref().unwrap_or_default();
+        let tags = git::tags(repository_dir(path))?
             .into_iter()
-            .map(|d| d.locate(&locator))
+            .filter_map(|(name, date)| Tag::from_pattern(&name, pattern, date))
+            .collect();
+        Some(tags)
+    } else {
+        None
+    };
+    let dir = repository_dir(path);
+    let unreleased_commits = match options.since {
+        Some(since) => {
+            let pattern = config.lint.git.tag_pattern.as_deref().unwrap_or_default();
+            let since = resolve_since(dir, since, pattern)?;
+            let count = git::count_commits(
+                dir,
+                &since,
+                config.lint.git.include.as_deref().unwrap_or_default(),
+                config.lint.git.exclude.as_deref().unwrap_or_default(),
+            )?;
+            Some(Commits::new(since, count))
+        }
+        None => None,
+    };
+    let (changes, released_versions) = match patch(path, options)? {
+        Some(patch) => {
+            // Releases that are not in the original changelog are new.
+            let original = diff::original(&patch, path, &content);
+            let versions = parse_changelog(path, &original, section, &config)
+                .map(|base| {
+                    base.releases
+                        .iter()
+                        .map(|r| r.version.value.to_string())
+                        .collect()
+                })
+                .unwrap_or_default();
+            (Some(diff::changes(&patch, path, &content)), Some(versions))
+        }
+        None => (None, None),
+    };
+    let linter = Linter::new(&ruleset)
+        .with_filename(Some(path))
+        .with_today(config.lint.today)
+        .with_tags(tags)
+        .with_date_tolerance(config.lint.git.date_tolerance.unwrap_or_default())
+        .with_unreleased_commits(unreleased_commits)
+        .with_changes(changes)
+        .with_released_versions(released_versions);
+    let mut diagnostics = linter.lint(&ir);
+    let mut baseline = None;
+    if options.write_baseline {
+        baseline = Some(Baseline::new(&diagnostics, &ir));
+    } else if let Some(b) = options.baseline {
+        diagnostics = b.filter(diagnostics, &ir);
+    }
+    Ok(Linted {
+        path: path.to_path_buf(),
+        content,
+        diagnostics,
+        baseline,
+    })
+}
+
+/// Return the git ref to count unreleased commits from: `since`, or the latest tag that matches
+/// `pattern` if `since` is empty.
+fn resolve_since(dir: &Path, since: &str, pattern: &str) -> Result<String> {
+    if !since.is_empty() {
+        return Ok(since.to_string());
+    }
+    git::latest_tag(dir, pattern)?.ok_or_else(|| {
+        Error::Usage(format!(
+            "--since: no tag matches `{}`; pass a ref with --since=REF",
+            pattern
+        ))
+    })
+}
+
+/// Return the unified diff to lint the changes of `path` in, if only changes are linted.
+fn patch(path: &Path, options: &LintOptions) -> Result<Option<String>> {
+    match &options.diff {
+        Some(Diff::Patch(patch)) => Ok(Some(patch.clone())),
+        Some(Diff::Base(base)) => Ok(Some(git::diff(repository_dir(path), base, path)?)),
+        None => Ok(None),
+    }
+}
+
+/// Lint a changelog fragment.
+fn lint_fragment(path: &Path, options: &LintOptions) -> Result<Linted> {
+    let content = std::fs::read_to_string(path).map_err(|e| Error::from(e).in_file(path))?;
+    let config = file_config(path, options)?;
+    let ruleset = RuleSet::new(config.lint.rules(path)?);
+    let mut diagnostics = fragments::lint(&Fragment::parse(path, &content), &ruleset);
+    for diagnostic in &mut diagnostics {
+        diagnostic.path = Some(path.to_path_buf());
+    }
+    if let Some(patch) = patch(path, options)? {
+        let changes = diff::changes(&patch, path, &content);
+        diagnostics.retain(|d| match d.location {
+            Some(location) => changes.iter().any(|change| change.overlaps(&location)),
+            None => true,
+        });
+    }
+    // Fragments have no sections, so the baseline identifies their diagnostics by content.
+    let ir = Parser::new().parse(&content);
+    let mut baseline = None;
+    if options.write_baseline {
+        baseline = Some(Baseline::new(&diagnostics, &ir));
+    } else if let Some(b) = options.baseline {
+        diagnostics = b.filter(diagnostics, &ir);
+    }
+    Ok(Linted {
+        path: path.to_path_buf(),
+        content,
+        diagnostics,
+        baseline,
+    })
+}
+
+/// Return the configuration for a file: the discovered configuration, overridden by the
+/// configuration file and flags given on the command line.
+fn file_config(path: &Path, options: &LintOptions) -> Result<Config> {
+    let mut config = Config::discover(repository_dir(path))?;
+    if let Some(config_file) = options.config_file {
+        config = config.merge(config_file);
+    }
+    Ok(config.merge(options.cli_config))
+}
+
+/// Return the region of the changelog in `content`: `section`, the content between markers, or
+/// the whole document.
+fn changelog_region(content: &str, section: Option<String>) -> Region {
+    match section {
+        Some(section) => Region::Section(section),
+        None if Region::Markers.find(content).is_some() => Region::Markers,
+        None => Region::Document,
+    }
+}
+
+/// Parse a changelog in a file or a section of a file.
+///
+/// Lint the content between markers if the file contains them, and detect the heading levels
+/// of embedded changelogs unless the configuration sets them.
+pub(super) fn parse_changelog<'a>(
+    path: &Path,
+    content: &'a str,
+    section: Option<String>,
+    config: &Config,
+) -> Result<ParsedChangelog<'a>> {
+    let region = changelog_region(content, section);
+    if let Region::Section(section) = &region
+        && region.find(content).is_none()
+    {
+        return Err(Error::SectionNotFound(format!(
+            "{}#{}",
+            path.display(),
+            section
+        )));
+    }
+    // Changelogs embedded in other documents usually use deeper headings.
+    let layout = config.lint.layout.unwrap_or(match region {
+        Region::Document => Layout::default(),
+        _ => Layout::Auto,
+    });
+    Ok(Parser::new()
+        .with_layout(layout)
+        .with_region(region)
+        .parse(content))
+}
+
+/// Apply `f` to each item on a pool of threads, preserving order.
+fn parallel_map<T: Sync, R: Send, F>(items: &[T], f: F) -> Vec<R>
+where
+    F: Fn(&T) -> R + Sync,
+{
+    let threads = std::thread::available_parallelism()
+        .map_or(1, |n| n.get())
+        .min(items.len());
+    let next = AtomicUsize::new(0);
+    let mut results: Vec<(usize, R)> = std::thread::scope(|scope| {
+        let handles: Vec<_> = (0..threads)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut results = Vec::new();
+                    loop {
+                        let i = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(item) = items.get(i) else {
+                            break results;
+                        };
+                        results.push((i, f(item)));
+                    }
+                })
+            })
             .collect();
-        let mut output = io::stdout();
-        diagnostics.sort_by_key(|d| d.location);
-        report(
-            &mut output,
-            diagnostics.as_slice(),
-            &content,
-            Some(&path),
-            &locator,
-            // TODO: Build final config.
-            config.lint.output_format.unwrap(),
-        )?;
-        Err(Error::Check)
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().unwrap())
+            .collect()
+    });
+    results.sort_by_key(|(i, _)| *i);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+/// Return `true` if `path` means standard input.
+fn is_stdin(path: &Path) -> bool {
+    path == Path::new("-")
+}
+
+/// Return the directory to run `git` in for a changelog.
+pub(super) fn repository_dir(path: &Path) -> &Path {
+    match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    }
+}
+
+/// Split a `FILE#SECTION` argument into a path and section anchor.
+///
+/// A path that exists is never split.
+fn split_section(path: &Path) -> (PathBuf, Option<String>) {
+    if !path.exists()
+        && let Some((file, section)) = path.to_str().and_then(|s| s.rsplit_once('#'))
+    {
+        return (PathBuf::from(file), Some(section.to_string()));
+    }
+    (path.to_path_buf(), None)
+}
+
+pub fn init(matches: &ArgMatches) -> Result<()> {
+    let path = matches.get_one::<PathBuf>("FILE").unwrap();
+    if !is_stdin(path) && path.exists() && !matches.get_flag("force") {
+        return Err(Error::Usage(format!(
+            "{} already exists; use --force to overwrite it",
+            path.display()
+        )));
+    }
+    let content = init_changelog(
+        repository_dir(path),
+        matches.get_one::<String>("url").map(String::as_str),
+        matches.get_flag("tags"),
+    )?;
+    if is_stdin(path) {
+        io::stdout().write_all(content.as_bytes())?;
+        return Ok(());
+    }
+    std::fs::write(path, content).map_err(|e| Error::from(e).in_file(path))
+}
+
+/// Render a new changelog for the repository in `dir`.
+///
+/// If `tags` is `true`, add a release with a placeholder entry for each tag that matches the tag
+/// pattern.
+fn init_changelog(dir: &Path, url: Option<&str>, tags: bool) -> Result<String> {
+    let url = resolve_url(dir, url);
+    let tags = if tags { release_tags(dir)? } else { vec![] };
+    let releases = tags
+        .iter()
+        .map(|tag| (tag, placeholder_changes()))
+        .collect();
+    Ok(render::changelog(
+        &template(&url, vec![], releases),
+        Some(render::PREAMBLE),
+    ))
+}
+
+/// Return a placeholder entry for a release whose changes are unknown.
+fn placeholder_changes() -> Vec<OwnedChanges> {
+    vec![OwnedChanges {
+        kind: "Changed".to_string(),
+        items: vec!["TODO: Describe the changes in this release".to_string()],
+    }]
+}
+
+pub fn generate(matches: &ArgMatches) -> Result<()> {
+    let content = generate_changelog(
+        Path::new("."),
+        matches.get_one::<String>("from_git").unwrap(),
+        matches.get_one::<String>("url").map(String::as_str),
+        matches.get_flag("tags"),
+    )?;
+    io::stdout().write_all(content.as_bytes())?;
+    Ok(())
+}
+
+/// Render a changelog from the Conventional Commits in `range` of the repository in `dir`.
+///
+/// If `tags` is `true`, add a release for each tag that matches the tag pattern, with a
+/// placeholder entry if none of its commits map to a change type. Otherwise, add all changes to
+/// the unreleased section.
+fn generate_changelog(dir: &Path, range: &str, url: Option<&str>, tags: bool) -> Result<String> {
+    let config = Config::discover(dir)?;
+    let types = config.generate.types.unwrap_or_default();
+    generate::validate(&types)?;
+    let url = resolve_url(dir, url);
+    let tags = if tags { release_tags(dir)? } else { vec![] };
+    let commits = git::commits(dir, range)?;
+    let (unreleased, releases) = generate::split(&commits, &tags);
+    let releases = releases
+        .into_iter()
+        .map(|(tag, commits)| {
+            let changes = generate::changes(commits, &types);
+            // A release without changes would fail the lint.
+            if changes.is_empty() {
+                (tag, placeholder_changes())
+            } else {
+                (tag, changes)
+            }
+        })
+        .collect();
+    Ok(render::changelog(
+        &template(&url, generate::changes(unreleased, &types), releases),
+        Some(render::PREAMBLE),
+    ))
+}
+
+/// The repository URL to use if there is none, so that the links still pass the lint.
+const PLACEHOLDER_URL: &str = "https://example.com/OWNER/REPOSITORY";
+
+/// Return `url`, or the web URL of the repository in `dir`, or a placeholder URL.
+fn resolve_url(dir: &Path, url: Option<&str>) -> String {
+    match url {
+        Some(url) => url.trim_end_matches('/').to_string(),
+        None => repository_url(dir).unwrap_or_else(|| {
+            eprintln!(
+                "warning: cannot find the repository URL in the git remote or Cargo.toml; using {} (set it with --url)",
+                PLACEHOLDER_URL
+            );
+            PLACEHOLDER_URL.to_string()
+        }),
+    }
+}
+
+/// Return the tags in the repository in `dir` that match the tag pattern, newest first.
+fn release_tags(dir: &Path) -> Result<Vec<Tag>> {
+    let config = Config::discover(dir)?;
+    let pattern = config.lint.git.tag_pattern.as_deref().unwrap_or_default();
+    let mut tags: Vec<Tag> = git::tags(dir)?
+        .into_iter()
+        .filter_map(|(name, date)| Tag::from_pattern(&name, pattern, date))
+        .collect();
+    tags.sort_by(|a, b| {
+        version_compare::compare(&b.version, &a.version)
+            .ok()
+            .and_then(|cmp| cmp.ord())
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then(b.date.cmp(&a.date))
+    });
+    Ok(tags)
+}
+
+/// Return a changelog for a repository at `url` with the given sections.
+///
+/// `releases` must be newest first. Each release links to the comparison with the previous
+/// release.
+fn template(
+    url: &str,
+    unreleased: Vec<OwnedChanges>,
+    releases: Vec<(&Tag, Vec<OwnedChanges>)>,
+) -> OwnedChangelog {
+    let unreleased_url = match releases.first() {
+        Some((tag, _)) => format!("{}/compare/{}...HEAD", url, tag.name),
+        None => format!("{}/commits/HEAD", url),
+    };
+    let previous: Vec<Option<&Tag>> = releases
+        .iter()
+        .skip(1)
+        .map(|(tag, _)| Some(*tag))
+        .chain([None])
+        .collect();
+    let releases = releases
+        .into_iter()
+        .zip(previous)
+        .map(|((tag, changes), previous)| OwnedRelease {
+            version: tag.version.clone(),
+            url: Some(match previous {
+                Some(previous) => format!("{}/compare/{}...{}", url, previous.name, tag.name),
+                None => format!("{}/releases/tag/{}", url, tag.name),
+            }),
+            date: tag.date.map(|date| date.to_string()),
+            yanked: false,
+            changes,
+        })
+        .collect();
+    OwnedChangelog {
+        title: Some("Changelog".to_string()),
+        unreleased: Some(OwnedUnreleased {
+            url: Some(unreleased_url),
+            changes: unreleased,
+        }),
+        releases,
+    }
+}
+
+/// Return the web URL of the repository in `dir` from the `origin` remote, or the `repository`
+/// field of `Cargo.toml`.
+fn repository_url(dir: &Path) -> Option<String> {
+    if let Ok(url) = git::remote_url(dir) {
+        return Some(url);
+    }
+    let manifest = std::fs::read_to_string(dir.join("Cargo.toml")).ok()?;
+    let manifest: toml::Table = toml::from_str(&manifest).ok()?;
+    let url = manifest.get("package")?.get("repository")?.as_str()?;
+    Some(git::web_url(url))
+}
+
+pub fn fragment(matches: &ArgMatches) -> Result<()> {
+    match matches.subcommand() {
+        Some(("new", submatches)) => {
+            let dir = Path::new(".");
+            let name = match submatches.get_one::<String>("name") {
+                Some(name) => name.clone(),
+                None => default_fragment_name(dir),
+            };
+            let path = new_fragment(
+                dir,
+                submatches.get_one::<String>("TYPE").unwrap(),
+                &name,
+                submatches.get_one::<String>("message").map(String::as_str),
+            )?;
+            println!("{}", path.display());
+            Ok(())
+        }
+        _ => unreachable!(),
+    }
+}
+
+/// Write a new fragment of change type `kind` to the `changelog.d` directory in `dir`, and return
+/// its path.
+///
+/// The file is named `NAME.TYPE.md`, with a numeric suffix if the name is taken.
+fn new_fragment(dir: &Path, kind: &str, name: &str, message: Option<&str>) -> Result<PathBuf> {
+    let kind = CHANGE_TYPES
+        .iter()
+        .find(|t| t.eq_ignore_ascii_case(kind))
+        .ok_or_else(|| {
+            Error::Usage(format!(
+                "invalid change type `{}` (expected one of: {})",
+                kind,
+                CHANGE_TYPES.join(", ")
+            ))
+        })?
+        .to_lowercase();
+    let dir = dir.join(fragments::DIRECTORY);
+    std::fs::create_dir_all(&dir).map_err(|e| Error::from(e).in_file(&dir))?;
+    let mut path = dir.join(format!("{}.{}.md", name, kind));
+    let mut n = 2;
+    while path.exists() {
+        path = dir.join(format!("{}-{}.{}.md", name, n, kind));
+        n += 1;
+    }
+    let message = message.unwrap_or("TODO: Describe the change");
+    std::fs::write(&path, format!("{}\n", message.trim_end()))
+        .map_err(|e| Error::from(e).in_file(&path))?;
+    Ok(path)
+}
+
+/// Return the name of the current git branch with unsafe characters replaced, or the current
+/// time if there is no branch.
+fn default_fragment_name(dir: &Path) -> String {
+    match git::branch(dir) {
+        Ok(Some(branch)) => branch
+            .chars()
+            .map(|c| match c {
+                'a'..='z' | 'A'..='Z' | '0'..='9' | '_' => c,
+                _ => '-',
+            })
+            .collect(),
+        _ => std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs())
+            .to_string(),
+    }
+}
+
+pub fn release(matches: &ArgMatches) -> Result<()> {
+    let path = matches.get_one::<PathBuf>("FILE").unwrap();
+    let version = matches.get_one::<String>("VERSION").unwrap();
+    let content = std::fs::read_to_string(path).map_err(|e| Error::from(e).in_file(path))?;
+    let dir = repository_dir(path);
+    let config = Config::discover(dir)?;
+    let date = matches
+        .get_one::<Date>("date")
+        .copied()
+        .unwrap_or_else(Date::today);
+    let fragments = fragments::find(dir)?
+        .into_iter()
+        .map(|path| {
+            let content =
+                std::fs::read_to_string(&path).map_err(|e| Error::from(e).in_file(&path))?;
+            Ok((path, content))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let content = release_changelog(path, &content, version, date, &fragments, &config)
+        .map_err(|e| e.in_file(path))?;
+    std::fs::write(path, content).map_err(|e| Error::from(e).in_file(path))?;
+    for (fragment, _) in &fragments {
+        std::fs::remove_file(fragment).map_err(|e| Error::from(e).in_file(fragment))?;
+    }
+    Ok(())
+}
+
+/// Return the changelog in `content` with its unreleased changes and `fragments` moved to a new
+/// release.
+fn release_changelog(
+    path: &Path,
+    content: &str,
+    version: &str,
+    date: Date,
+    fragments: &[(PathBuf, String)],
+    config: &Config,
+) -> Result<String> {
+    let ir = parse_changelog(path, content, None, config)?;
+    if ir.releases.iter().any(|r| *r.version == *version) {
+        return Err(Error::Usage(format!(
+            "version {} is already released",
+            version
+        )));
+    }
+    let unreleased = ir
+        .unreleased
+        .as_ref()
+        .ok_or_else(|| Error::Usage("the changelog has no unreleased section".to_string()))?;
+    let changes = release::changes(fragments)?;
+    if changes.is_empty() && unreleased.changes.iter().all(|c| c.items.is_empty()) {
+        return Err(Error::Usage(
+            "there are no unreleased changes or fragments to release".to_string(),
+        ));
+    }
+    let pattern = config.lint.git.tag_pattern.as_deref().unwrap_or_default();
+    let tag = pattern.replace("{version}", version);
+    let (unreleased_url, url) = match &unreleased.url {
+        Some(url) => {
+            let (unreleased_url, url) = release::links(url, &tag)?;
+            (Some(unreleased_url), Some(url))
+        }
+        None => (None, None),
+    };
+    let release = OwnedRelease {
+        version: version.to_string(),
+        url,
+        date: Some(date.to_string()),
+        yanked: false,
+        changes,
+    };
+    let region = changelog_region(content, None)
+        .find(content)
+        .unwrap_or(Span::new(0, content.len()));
+    release::insert(content, &ir, region, &release, unreleased_url.as_deref())
+}
+
+pub fn diff(matches: &ArgMatches) -> Result<()> {
+    let paths: Vec<&PathBuf> = matches
+        .get_many::<PathBuf>("FILE")
+        .map(|values| values.collect())
+        .unwrap_or_default();
+    let default = PathBuf::from("CHANGELOG.md");
+    let (old_path, new_path, old, new) = match matches.get_one::<String>("git") {
+        Some(range) => {
+            let path = match paths.as_slice() {
+                [] => &default,
+                [path] => *path,
+                _ => {
+                    return Err(Error::Usage(
+                        "--git takes at most one changelog".to_string(),
+                    ));
+                }
+            };
+            let (old, new) = read_revisions(path, range)?;
+            (path, path, old, new)
+        }
+        None => match paths.as_slice() {
+            [old_path, new_path] => (*old_path, *new_path, read(old_path)?, read(new_path)?),
+            _ => {
+                return Err(Error::Usage(
+                    "expected two changelogs, or one changelog and --git".to_string(),
+                ));
+            }
+        },
+    };
+    let config = Config::discover(repository_dir(new_path))?;
+    let changes = compare::compare(
+        &parse_changelog(old_path, &old, None, &config)?,
+        &parse_changelog(new_path, &new, None, &config)?,
+    );
+    let format = matches
+        .get_one::<DataFormat>("output_format")
+        .copied()
+        .unwrap_or_default();
+    compare::report(&mut io::stdout(), &changes, format)?;
+    Ok(())
+}
+
+/// Return the content of the changelog at `path` at both ends of a git revision range.
+///
+/// A range `A..B` compares the changelog at `A` and `B`. A single revision `A` compares the
+/// changelog at `A` with the working tree.
+fn read_revisions(path: &Path, range: &str) -> Result<(String, String)> {
+    let dir = repository_dir(path);
+    match range.split_once("..") {
+        Some((old, new)) => {
+            let old = if old.is_empty() { "HEAD" } else { old };
+            let new = if new.is_empty() { "HEAD" } else { new };
+            Ok((git::show(dir, old, path)?, git::show(dir, new, path)?))
+        }
+        None => Ok((git::show(dir, range, path)?, read(path)?)),
+    }
+}
+
+fn read(path: &Path) -> Result<String> {
+    std::fs::read_to_string(path).map_err(|e| Error::from(e).in_file(path))
+}
+
+pub fn query(matches: &ArgMatches) -> Result<()> {
+    let path = matches.get_one::<PathBuf>("FILE").unwrap();
+    let content = if is_stdin(path) {
+        let mut content = String::new();
+        io::stdin().read_to_string(&mut content)?;
+        content
+    } else {
+        read(path)?
+    };
+    let config = Config::discover(repository_dir(path))?;
+    let changelog = parse_changelog(path, &content, None, &config)?;
+    let result = query::query(
+        &query::value(&changelog.to_owned()),
+        matches.get_one::<String>("QUERY").unwrap(),
+    )?;
+    let format = matches
+        .get_one::<DataFormat>("output_format")
+        .copied()
+        .unwrap_or_default();
+    query::print(&mut io::stdout(), &result, format)?;
+    // Let scripts test for missing values with the exit status.
+    if result.is_null() {
+        return Err(Error::Check);
     }
+    Ok(())
+}
+
+pub fn stats(matches: &ArgMatches) -> Result<()> {
+    let path = matches.get_one::<PathBuf>("FILE").unwrap();
+    let content = read(path)?;
+    let config = Config::discover(repository_dir(path))?;
+    let changelog = parse_changelog(path, &content, None, &config)?;
+    let format = matches
+        .get_one::<DataFormat>("output_format")
+        .copied()
+        .unwrap_or_default();
+    stats::report(&mut io::stdout(), &stats::stats(&changelog), format)?;
+    Ok(())
 }
 
 pub fn rule(matches: &ArgMatches) -> Result<()> {
@@ -108,3 +935,257 @@ pub fn rule(matches: &ArgMatches) -> Result<()> {
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::cli::git::tests::Repository;
+
+    #[test]
+    fn test_split_section() {
+        assert_eq!(
+            split_section(Path::new("README.md#changelog")),
+            (PathBuf::from("README.md"), Some("changelog".to_string()))
+        );
+        assert_eq!(
+            split_section(Path::new("CHANGELOG.md")),
+            (PathBuf::from("CHANGELOG.md"), None)
+        );
+        assert_eq!(
+            split_section(Path::new("src")),
+            (PathBuf::from("src"), None)
+        );
+    }
+
+    #[test]
+    fn test_init_changelog() {
+        let repository = Repository::new();
+        repository.commit("README.md", "foo", "Initial commit", "2025-01-01");
+        repository.git(["tag", "v0.1.0"]);
+        repository.commit("README.md", "bar", "Update README", "2025-02-01");
+        repository.git(["tag", "v0.2.0"]);
+        repository.git(["tag", "not-a-release"]);
+        repository.commit("README.md", "baz", "Update README", "2025-03-01");
+        repository.git(["tag", "v0.10.0"]);
+        let dir = repository.path();
+        // Without a remote or Cargo.toml, the links use a placeholder URL.
+        let content = init_changelog(dir, None, false).unwrap();
+        assert!(content.contains(&format!("[unreleased]: {}/commits/HEAD", PLACEHOLDER_URL)));
+        assert_eq!(Linter::default().lint(&crate::parse(&content)), vec![]);
+        std::fs::write(
+            dir.join("Cargo.toml"),
+            "[package]\nrepository = \"https://github.com/owner/cargo\"\n",
+        )
+        .unwrap();
+        assert!(
+            init_changelog(dir, None, false)
+                .unwrap()
+                .contains("[unreleased]: https://github.com/owner/cargo/commits/HEAD")
+        );
+        repository.git(["remote", "add", "origin", "git@github.com:owner/repo.git"]);
+        let content = init_changelog(dir, None, true).unwrap();
+        insta::assert_snapshot!(content);
+        let changelog = crate::parse(&content);
+        assert_eq!(Linter::default().lint(&changelog), vec![]);
+    }
+
+    #[test]
+    fn test_init_changelog_empty() {
+        let repository = Repository::new();
+        let content =
+            init_changelog(repository.path(), Some("https://example.org/"), true).unwrap();
+        insta::assert_snapshot!(content);
+        let changelog = crate::parse(&content);
+        assert_eq!(Linter::default().lint(&changelog), vec![]);
+    }
+
+    #[test]
+    fn test_generate_changelog() {
+        let repository = Repository::new();
+        repository.commit("README.md", "a", "feat: add foo", "2025-01-01");
+        repository.commit("README.md", "b", "chore: update README", "2025-01-02");
+        repository.git(["tag", "v0.1.0"]);
+        repository.commit("README.md", "c", "fix(cli): handle bar", "2025-02-01");
+        repository.commit("README.md", "d", "feat!: remove baz", "2025-02-01");
+        repository.git(["tag", "v0.2.0"]);
+        repository.commit("README.md", "e", "perf: speed up qux", "2025-03-01");
+        let dir = repository.path();
+        let url = Some("https://example.org");
+        let content = generate_changelog(dir, "HEAD", url, true).unwrap();
+        insta::assert_snapshot!(content);
+        let changelog = crate::parse(&content);
+        assert_eq!(Linter::default().lint(&changelog), vec![]);
+
+        repository.git(["tag", "v0.3.0"]);
+        repository.commit("README.md", "f", "chore: bump version", "2025-03-02");
+        repository.git(["tag", "v0.4.0"]);
+        let content = generate_changelog(dir, "v0.3.0..HEAD", url, true).unwrap();
+        assert!(content.contains(
+            "## [0.4.0] - 2025-03-02\n\n### Changed\n\n* TODO: Describe the changes in this release\n"
+        ));
+        assert_eq!(Linter::default().lint(&crate::parse(&content)), vec![]);
+
+        let content = generate_changelog(dir, "v0.1.0..HEAD", url, false).unwrap();
+        insta::assert_snapshot!("generate_changelog_unreleased", content);
+
+        std::fs::write(
+            dir.join("nb.toml"),
+            "[generate.types]\nchore = \"Changed\"\n",
+        )
+        .unwrap();
+        let content = generate_changelog(dir, "v0.1.0", url, false).unwrap();
+        assert!(content.contains("### Changed\n\n* Update README\n"));
+        assert!(!content.contains("Add foo"));
+
+        std::fs::write(
+            dir.join("nb.toml"),
+            "[generate.types]\nchore = \"Chores\"\n",
+        )
+        .unwrap();
+        assert!(matches!(
+            generate_changelog(dir, "HEAD", url, false),
+            Err(Error::Config(_))
+        ));
+    }
+
+    #[test]
+    fn test_new_fragment() {
+        let repository = Repository::new();
+        let dir = repository.path();
+        let path = new_fragment(dir, "Fixed", "123", Some("Fix foo")).unwrap();
+        assert_eq!(path, dir.join("changelog.d/123.fixed.md"));
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "Fix foo\n");
+        let path = new_fragment(dir, "fixed", "123", None).unwrap();
+        assert_eq!(path, dir.join("changelog.d/123-2.fixed.md"));
+        assert!(matches!(
+            new_fragment(dir, "features", "123", None),
+            Err(Error::Usage(_))
+        ));
+        repository.commit("README.md", "foo", "Initial commit", "2025-01-01");
+        repository.git(["checkout", "--quiet", "-b", "feature/foo.bar"]);
+        assert_eq!(default_fragment_name(dir), "feature-foo-bar");
+    }
+
+    #[test]
+    fn test_release_changelog() {
+        let source = "\
+# Changelog
+
+## [Unreleased]
+
+### Fixed
+
+* Fix foo
+
+## [1.0.0] - 2025-01-01
+
+### Added
+
+* Add foo
+
+[unreleased]: https://example.org/compare/v1.0.0...HEAD
+[1.0.0]: https://example.org/releases/tag/v1.0.0
+";
+        let path = Path::new("CHANGELOG.md");
+        let config = Config::default();
+        let date = Date::new(2025, 2, 1).unwrap();
+        let fragments = [
+            (
+                PathBuf::from("changelog.d/2.fixed.md"),
+                "Fix bar\n".to_string(),
+            ),
+            (
+                PathBuf::from("changelog.d/1.md"),
+                "---\ntype: added\n---\n\n* Add baz\n".to_string(),
+            ),
+        ];
+        let content = release_changelog(path, source, "1.1.0", date, &fragments, &config).unwrap();
+        insta::assert_snapshot!(content);
+        assert_eq!(Linter::default().lint(&crate::parse(&content)), vec![]);
+
+        assert!(matches!(
+            release_changelog(path, source, "1.0.0", date, &[], &config),
+            Err(Error::Usage(_))
+        ));
+        let released = release_changelog(path, &content, "1.2.0", date, &[], &config);
+        assert!(matches!(released, Err(Error::Usage(_))));
+    }
+
+    #[test]
+    fn test_release_changelog_markers() {
+        let source = "\
+# Project
+
+<!-- changelog:start -->
+## Changelog
+
+### [Unreleased]
+
+#### Fixed
+
+* Fix foo
+
+[unreleased]: https://example.org/commits/HEAD
+<!-- changelog:end -->
+
+## License
+
+MIT
+";
+        let path = Path::new("README.md");
+        let date = Date::new(2025, 2, 1).unwrap();
+        let fragments = [
+            (
+                PathBuf::from("changelog.d/1.fixed.md"),
+                "Fix bar\n".to_string(),
+            ),
+            (
+                PathBuf::from("changelog.d/2.security.md"),
+                "Fix baz\n".to_string(),
+            ),
+        ];
+        let content =
+            release_changelog(path, source, "1.0.0", date, &fragments, &Config::default()).unwrap();
+        insta::assert_snapshot!(content);
+        assert!(content.ends_with("<!-- changelog:end -->\n\n## License\n\nMIT\n"));
+    }
+
+    #[test]
+    fn test_resolve_since() {
+        let repository = Repository::new();
+        repository.commit("README.md", "a", "Initial commit", "2025-01-01");
+        let dir = repository.path();
+        assert_eq!(resolve_since(dir, "HEAD", "v{version}").unwrap(), "HEAD");
+        assert!(matches!(
+            resolve_since(dir, "", "v{version}"),
+            Err(Error::Usage(_))
+        ));
+        repository.git(["tag", "v1.0.0"]);
+        assert_eq!(resolve_since(dir, "", "v{version}").unwrap(), "v1.0.0");
+    }
+
+    #[test]
+    fn test_read_revisions() {
+        let repository = Repository::new();
+        repository.commit("CHANGELOG.md", "a\n", "Initial commit", "2025-01-01");
+        repository.commit("CHANGELOG.md", "b\n", "Update changelog", "2025-01-02");
+        let path = repository.path().join("CHANGELOG.md");
+        std::fs::write(&path, "c\n").unwrap();
+        let read = |range| read_revisions(&path, range).unwrap();
+        assert_eq!(read("HEAD~1..HEAD"), ("a\n".to_string(), "b\n".to_string()));
+        assert_eq!(read("HEAD~1.."), ("a\n".to_string(), "b\n".to_string()));
+        assert_eq!(read("HEAD"), ("b\n".to_string(), "c\n".to_string()));
+        assert!(matches!(read_revisions(&path, "nope"), Err(Error::Git(_))));
+    }
+
+    #[test]
+    fn test_parallel_map() {
+        let items: Vec<usize> = (0..100).collect();
+        assert_eq!(
+            parallel_map(&items, |i| i * 2),
+            items.iter().map(|i| i * 2).collect::<Vec<_>>()
+        );
+        assert!(parallel_map(&[] as &[usize], |i| *i).is_empty());
+    }
+}
@@ -0,0 +1,338 @@
+//! Compare two versions of a changelog.
+use std::io::Write;
+
+use serde::Serialize;
+
+use crate::changelog::traits::{Changelog, Changes, Release, Unreleased};
+
+use super::report::DataFormat;
+
+/// The name of the unreleased section in changes.
+const UNRELEASED: &str = "Unreleased";
+
+/// A difference between two versions of a changelog.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(tag = "change", rename_all = "snake_case")]
+pub enum Change {
+    /// A release was added.
+    AddedRelease { version: String },
+    /// A release was removed.
+    RemovedRelease { version: String },
+    /// The URL, date, or yanked status of a release changed.
+    ModifiedRelease {
+        version: String,
+        field: &'static str,
+        old: Option<String>,
+        new: Option<String>,
+    },
+    /// An entry was added to a section.
+    AddedEntry {
+        section: String,
+        kind: String,
+        entry: String,
+        published: bool,
+    },
+    /// An entry was removed from a section.
+    RemovedEntry {
+        section: String,
+        kind: String,
+        entry: String,
+        published: bool,
+    },
+    /// An entry moved to another change type in the same section.
+    MovedEntry {
+        section: String,
+        from: String,
+        to: String,
+        entry: String,
+        published: bool,
+    },
+}
+
+/// Return the differences between `old` and `new`.
+///
+/// Releases are matched by version. Changes to releases that are in both versions are marked as
+/// published, since they edit a release that was already out. Entries are compared by text, so an
+/// edited entry is a removed entry and an added entry.
+pub fn compare<A: Changelog, B: Changelog>(old: &A, new: &B) -> Vec<Change> {
+    let mut changes = Vec::new();
+    let old_unreleased = old.unreleased().map(|u| entries(u.changes()));
+    let new_unreleased = new.unreleased().map(|u| entries(u.changes()));
+    compare_entries(
+        &mut changes,
+        UNRELEASED,
+        &old_unreleased.unwrap_or_default(),
+        &new_unreleased.unwrap_or_default(),
+        false,
+    );
+    for release in new.releases() {
+        let version = release.version();
+        let Some(previous) = old.releases().iter().find(|r| r.version() == version) else {
+            changes.push(Change::AddedRelease {
+                version: version.to_string(),
+            });
+            continue;
+        };
+        let fields = [
+            (
+                "url",
+                previous.url().map(str::to_string),
+                release.url().map(str::to_string),
+            ),
+            (
+                "date",
+                previous.date().map(str::to_string),
+                release.date().map(str::to_string),
+            ),
+            (
+                "yanked",
+                Some(previous.yanked().to_string()),
+                Some(release.yanked().to_string()),
+            ),
+        ];
+        for (field, old, new) in fields {
+            if old != new {
+                changes.push(Change::ModifiedRelease {
+                    version: version.to_string(),
+                    field,
+                    old,
+                    new,
+                });
+            }
+        }
+        compare_entries(
+            &mut changes,
+            version,
+            &entries(previous.changes()),
+            &entries(release.changes()),
+            true,
+        );
+    }
+    for release in old.releases() {
+        if !new
+            .releases()
+            .iter()
+            .any(|r| r.version() == release.version())
+        {
+            changes.push(Change::RemovedRelease {
+                version: release.version().to_string(),
+            });
+        }
+    }
+    changes
+}
+
+/// Return the entries of a section as change types and items.
+fn entries<C: Changes>(changes: &[C]) -> Vec<(String, String)> {
+    changes
+        .iter()
+        .flat_map(|c| {
+            c.items()
+                .map(|item| (c.kind().to_string(), item.to_string()))
+        })
+        .collect()
+}
+
+fn compare_entries(
+    changes: &mut Vec<Change>,
+    section: &str,
+    old: &[(String, String)],
+    new: &[(String, String)],
+    published: bool,
+) {
+    // Remove entries that are in both versions, counting duplicates.
+    let mut removed: Vec<&(String, String)> = old.iter().collect();
+    let mut added = Vec::new();
+    for entry in new {
+        match removed.iter().position(|e| *e == entry) {
+            Some(i) => {
+                removed.remove(i);
+            }
+            None => added.push(entry),
+        }
+    }
+    let mut moved = Vec::new();
+    added.retain(
+        |(kind, entry)| match removed.iter().position(|(_, e)| e == entry) {
+            Some(i) => {
+                let (from, _) = removed.remove(i);
+                moved.push(Change::MovedEntry {
+                    section: section.to_string(),
+                    from: from.clone(),
+                    to: kind.clone(),
+                    entry: entry.clone(),
+                    published,
+                });
+                false
+            }
+            None => true,
+        },
+    );
+    changes.extend(
+        removed
+            .into_iter()
+            .map(|(kind, entry)| Change::RemovedEntry {
+                section: section.to_string(),
+                kind: kind.clone(),
+                entry: entry.clone(),
+                published,
+            }),
+    );
+    changes.extend(added.into_iter().map(|(kind, entry)| Change::AddedEntry {
+        section: section.to_string(),
+        kind: kind.clone(),
+        entry: entry.clone(),
+        published,
+    }));
+    changes.extend(moved);
+}
+
+/// Write the changes in `format`.
+pub fn report<W: Write>(w: &mut W, changes: &[Change], format: DataFormat) -> std::io::Result<()> {
+    match format {
+        DataFormat::Text => {
+            for change in changes {
+                writeln!(w, "{}", text(change))?;
+            }
+            Ok(())
+        }
+        DataFormat::Json => {
+            serde_json::to_writer_pretty(&mut *w, changes)?;
+            writeln!(w)
+        }
+    }
+}
+
+fn text(change: &Change) -> String {
+    let published = |published: bool| if published { " (published)" } else { "" };
+    match change {
+        Change::AddedRelease { version } => format!("+ release {}", version),
+        Change::RemovedRelease { version } => format!("- release {}", version),
+        Change::ModifiedRelease {
+            version,
+            field,
+            old,
+            new,
+        } => format!(
+            "~ release {}: {} {} -> {} (published)",
+            version,
+            field,
+            old.as_deref().unwrap_or("none"),
+            new.as_deref().unwrap_or("none")
+        ),
+        Change::AddedEntry {
+            section,
+            kind,
+            entry,
+            published: p,
+        } => format!("+ {} / {}: {}{}", section, kind, entry, published(*p)),
+        Change::RemovedEntry {
+            section,
+            kind,
+            entry,
+            published: p,
+        } => format!("- {} / {}: {}{}", section, kind, entry, published(*p)),
+        Change::MovedEntry {
+            section,
+            from,
+            to,
+            entry,
+            published: p,
+        } => format!(
+            "~ {} / {} -> {}: {}{}",
+            section,
+            from,
+            to,
+            entry,
+            published(*p)
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::parse;
+
+    const OLD: &str = "\
+# Changelog
+
+## [Unreleased]
+
+### Added
+
+* Add foo
+* Add bar
+
+## [1.0.0] - 2025-01-01
+
+### Fixed
+
+* Fix foo
+
+## [0.1.0] - 2024-12-01
+
+### Added
+
+* Add baz
+
+[unreleased]: https://example.org/compare/v1.0.0...HEAD
+[1.0.0]: https://example.org/compare/v0.1.0...v1.0.0
+[0.1.0]: https://example.org/releases/tag/v0.1.0
+";
+
+    const NEW: &str = "\
+# Changelog
+
+## [Unreleased]
+
+### Changed
+
+* Add bar
+
+## [1.1.0] - 2025-02-01
+
+### Added
+
+* Add foo
+
+## [1.0.0] - 2025-01-02
+
+### Fixed
+
+* Fix foo
+* Fix qux
+
+[unreleased]: https://example.org/compare/v1.1.0...HEAD
+[1.1.0]: https://example.org/compare/v1.0.0...v1.1.0
+[1.0.0]: https://example.org/compare/v0.1.0...v1.0.0
+";
+
+    #[test]
+    fn test_compare() {
+        let changes = compare(&parse(OLD), &parse(NEW));
+        let mut text = Vec::new();
+        report(&mut text, &changes, DataFormat::Text).unwrap();
+        insta::assert_snapshot!(String::from_utf8(text).unwrap());
+        insta::assert_yaml_snapshot!(changes);
+        assert_eq!(compare(&parse(OLD), &parse(OLD)), vec![]);
+    }
+
+    #[test]
+    fn test_compare_duplicates() {
+        let link = "[unreleased]: https://example.org/\n";
+        let old = format!("## [Unreleased]\n\n### Added\n\n* Foo\n* Foo\n\n{}", link);
+        let new = format!("## [Unreleased]\n\n### Added\n\n* Foo\n\n{}", link);
+        let (old, new) = (parse(&old), parse(&new));
+        assert_eq!(
+            compare(&old, &new),
+            vec![Change::RemovedEntry {
+                section: "Unreleased".to_string(),
+                kind: "Added".to_string(),
+                entry: "Foo".to_string(),
+                published: false,
+            }]
+        );
+    }
+}
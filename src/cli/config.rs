@@ -1,88 +1,258 @@
-use std::collections::HashSet;
-use std::path::Path;
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Component, Path, PathBuf};
+use std::sync::Mutex;
 
+use globset::{GlobBuilder, GlobMatcher};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use toml;
 
+use crate::date::Date;
+use crate::parser::Layout;
 use crate::rule::Rule;
+use crate::span::Encoding;
 
-use super::error::Result;
+use super::error::{Error, Result};
 use super::report::Format;
 
 #[derive(Debug, Default, PartialEq, Deserialize, Serialize)]
 #[serde(default)]
 pub struct Config {
+    /// The path of a configuration file to extend, relative to this file.
+    pub extends: Option<PathBuf>,
     // When deserializing from a file, we want to consider empty values unset instead of their
     // default values.
     #[serde(default = "Lint::empty")]
     pub lint: Lint,
+    #[serde(default = "Generate::empty")]
+    pub generate: Generate,
 }
 
 #[derive(Debug, PartialEq, Deserialize, Serialize)]
 pub struct Lint {
-    #[serde(default = "default_select")]
     pub select: Option<HashSet<Rule>>,
     pub ignore: Option<HashSet<Rule>>,
     pub output_format: Option<Format>,
+    pub column_encoding: Option<Encoding>,
+    pub layout: Option<Layout>,
+    pub today: Option<Date>,
+    #[serde(default)]
+    pub git: Git,
+    #[serde(default)]
+    pub overrides: Vec<Override>,
 }
 
-#[derive(Debug, PartialEq, Deserialize, Serialize)]
+/// Settings for files that match glob patterns.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+pub struct Override {
+    pub files: Vec<String>,
+    pub select: Option<HashSet<Rule>>,
+    pub ignore: Option<HashSet<Rule>>,
+    /// The directory that `files` are relative to.
+    #[serde(skip)]
+    pub base: PathBuf,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+pub struct Git {
+    pub enabled: Option<bool>,
+    pub tag_pattern: Option<String>,
+    pub date_tolerance: Option<u32>,
+    pub include: Option<Vec<String>>,
+    pub exclude: Option<Vec<String>>,
+}
+
+/// Settings for generating changelogs from commit history.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct Generate {
+    /// The change type for each Conventional Commits type. Commits of other types are skipped.
+    pub types: Option<BTreeMap<String, String>>,
+}
+
+#[derive(Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
 struct PyProjectConfig {
     tool: PyProjectTool,
 }
 
 #[derive(Debug, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
 struct PyProjectTool {
     nb: Config,
 }
 
+#[derive(Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+struct CargoConfig {
+    workspace: CargoTable,
+    package: CargoTable,
+}
+
+#[derive(Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+struct CargoTable {
+    metadata: CargoMetadata,
+}
+
+#[derive(Debug, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+struct CargoMetadata {
+    nb: Config,
+}
+
+#[derive(Debug, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+struct PackageJsonConfig {
+    nb: Config,
+}
+
 impl Config {
-    #[cfg(test)]
     pub fn empty() -> Self {
         Self {
+            extends: None,
             lint: Lint::empty(),
+            generate: Generate::empty(),
         }
     }
 
     pub fn merge(&self, other: &Config) -> Self {
         Self {
+            extends: other.extends.clone().or(self.extends.clone()),
             lint: self.lint.merge(&other.lint),
+            generate: self.generate.merge(&other.generate),
         }
     }
 
-    // Load default configuration
-    pub fn load(path: Option<&Path>) -> Result<Config> {
-        let mut config = Config::default();
-        // TODO: Add debug logging for I/O error.
-        if let Ok(s) = std::fs::read_to_string("pyproject.toml") {
-            let pyproject = PyProjectConfig::from_str(&s)?;
-            config = config.merge(&pyproject.tool.nb);
-        }
-        if let Ok(s) = std::fs::read_to_string("nb.toml") {
-            config = config.merge(&Config::from_str(&s)?);
+    /// Load the configuration for files in `dir`.
+    ///
+    /// Search `dir` and its parents, up to the root of the git repository, for `pyproject.toml`,
+    /// `Cargo.toml`, `package.json`, and `nb.toml` files. Files in nearer directories take
+    /// precedence. Within a directory, later files in that list take precedence.
+    pub fn discover(dir: &Path) -> Result<Config> {
+        let dir = normalize(&std::path::absolute(dir)?);
+        let mut dirs = Vec::new();
+        for dir in dir.ancestors() {
+            dirs.push(dir);
+            if dir.join(".git").exists() {
+                break;
+            }
         }
-        // Return an error if opening the user-specified file fails.
-        if let Some(path) = path {
-            let s = std::fs::read_to_string(path)?;
-            let other = Config::from_str(&s)?;
-            config = config.merge(&other);
+        let mut config = Config::default();
+        for dir in dirs.into_iter().rev() {
+            let path = dir.join("pyproject.toml");
+            if path.is_file() {
+                config = config.merge(&PyProjectConfig::from_file(&path)?.tool.nb);
+            }
+            let path = dir.join("Cargo.toml");
+            if path.is_file() {
+                let cargo = CargoConfig::from_file(&path)?;
+                config = config
+                    .merge(&cargo.workspace.metadata.nb)
+                    .merge(&cargo.package.metadata.nb);
+            }
+            let path = dir.join("package.json");
+            if path.is_file() {
+                config = config.merge(&PackageJsonConfig::from_file(&path)?.nb);
+            }
+            let path = dir.join("nb.toml");
+            if path.is_file() {
+                config = config.merge(&Config::from_file(&path)?);
+            }
         }
         Ok(config)
     }
 
+    #[cfg(test)]
     pub fn from_str(s: &str) -> Result<Self> {
         Ok(toml::from_str(s)?)
     }
 
+    /// Load a configuration file.
+    ///
+    /// Resolve `extends` and make `overrides` relative to the directory containing the file.
     pub fn from_file(path: &Path) -> Result<Self> {
-        let s = std::fs::read_to_string(path)?;
-        Self::from_str(&s)
+        parse_file::<Self>(path, &[""])?.resolve(path, &mut Vec::new())
+    }
+
+    fn resolve(mut self, path: &Path, seen: &mut Vec<PathBuf>) -> Result<Self> {
+        let path = normalize(&std::path::absolute(path)?);
+        if seen.contains(&path) {
+            return Err(Error::Config(format!(
+                "circular `extends` in {}",
+                path.display()
+            )));
+        }
+        let dir = path.parent().unwrap_or(Path::new("/"));
+        for o in self.lint.overrides.iter_mut() {
+            for glob in &o.files {
+                build_glob(glob).map_err(|e| e.in_file(&path))?;
+            }
+            o.base = dir.to_path_buf();
+        }
+        seen.push(path.clone());
+        match self.extends.take() {
+            Some(extends) => {
+                let extends = dir.join(extends);
+                let base = parse_file::<Self>(&extends, &[""])?.resolve(&extends, seen)?;
+                Ok(base.merge(&self))
+            }
+            None => Ok(self),
+        }
+    }
+}
+
+// Missing tables are unset instead of their default values.
+impl Default for PyProjectTool {
+    fn default() -> Self {
+        Self {
+            nb: Config::empty(),
+        }
+    }
+}
+
+impl Default for CargoMetadata {
+    fn default() -> Self {
+        Self {
+            nb: Config::empty(),
+        }
+    }
+}
+
+impl Default for PackageJsonConfig {
+    fn default() -> Self {
+        Self {
+            nb: Config::empty(),
+        }
     }
 }
 
 impl PyProjectConfig {
-    pub fn from_str(s: &str) -> Result<Self> {
-        Ok(toml::from_str(s)?)
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let mut config: Self = parse_file(path, &["tool.nb."])?;
+        config.tool.nb = std::mem::replace(&mut config.tool.nb, Config::empty())
+            .resolve(path, &mut Vec::new())?;
+        Ok(config)
+    }
+}
+
+impl CargoConfig {
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let mut config: Self =
+            parse_file(path, &["workspace.metadata.nb.", "package.metadata.nb."])?;
+        for table in [&mut config.workspace, &mut config.package] {
+            table.metadata.nb = std::mem::replace(&mut table.metadata.nb, Config::empty())
+                .resolve(path, &mut Vec::new())?;
+        }
+        Ok(config)
+    }
+}
+
+impl PackageJsonConfig {
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let mut config: Self = parse_file(path, &["nb."])?;
+        config.nb =
+            std::mem::replace(&mut config.nb, Config::empty()).resolve(path, &mut Vec::new())?;
+        Ok(config)
     }
 }
 
@@ -92,6 +262,11 @@ impl Lint {
             select: Default::default(),
             ignore: Default::default(),
             output_format: Default::default(),
+            column_encoding: Default::default(),
+            layout: Default::default(),
+            today: Default::default(),
+            git: Default::default(),
+            overrides: Default::default(),
         }
     }
 
@@ -100,8 +275,46 @@ impl Lint {
             select: other.select.clone().or(self.select.clone()),
             ignore: other.ignore.clone().or(self.ignore.clone()),
             output_format: other.output_format.or(self.output_format),
+            column_encoding: other.column_encoding.or(self.column_encoding),
+            layout: other.layout.or(self.layout),
+            today: other.today.or(self.today),
+            git: self.git.merge(&other.git),
+            overrides: [self.overrides.as_slice(), other.overrides.as_slice()].concat(),
         }
     }
+
+    /// Return the rules enabled for the file at `path`.
+    ///
+    /// Overrides that match `path` take precedence over the top-level settings, and later
+    /// overrides take precedence over earlier ones.
+    pub fn rules(&self, path: &Path) -> Result<HashSet<Rule>> {
+        let mut select = self.select.clone().unwrap_or_default();
+        let mut ignore = self.ignore.clone().unwrap_or_default();
+        for o in &self.overrides {
+            if o.matches(path)? {
+                select = o.select.clone().unwrap_or(select);
+                ignore = o.ignore.clone().unwrap_or(ignore);
+            }
+        }
+        Ok(select.difference(&ignore).copied().collect())
+    }
+}
+
+impl Override {
+    /// Return `true` if the file at `path` matches any of the `files` globs.
+    pub fn matches(&self, path: &Path) -> Result<bool> {
+        let path = normalize(&std::path::absolute(path)?);
+        let base = normalize(&std::path::absolute(&self.base)?);
+        let Ok(path) = path.strip_prefix(&base) else {
+            return Ok(false);
+        };
+        for glob in &self.files {
+            if build_glob(glob)?.is_match(path) {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
 }
 
 impl Default for Lint {
@@ -110,6 +323,65 @@ impl Default for Lint {
             select: default_select(),
             ignore: Some(Default::default()),
             output_format: Some(Default::default()),
+            column_encoding: Some(Default::default()),
+            // The default depends on the region of the document to lint.
+            layout: None,
+            today: None,
+            git: Git {
+                enabled: Some(false),
+                tag_pattern: Some("v{version}".to_string()),
+                date_tolerance: Some(1),
+                include: Some(vec![]),
+                exclude: Some(vec![]),
+            },
+            overrides: vec![],
+        }
+    }
+}
+
+impl Git {
+    pub fn merge(&self, other: &Git) -> Self {
+        Self {
+            enabled: other.enabled.or(self.enabled),
+            tag_pattern: other.tag_pattern.clone().or(self.tag_pattern.clone()),
+            date_tolerance: other.date_tolerance.or(self.date_tolerance),
+            include: other.include.clone().or(self.include.clone()),
+            exclude: other.exclude.clone().or(self.exclude.clone()),
+        }
+    }
+}
+
+impl Generate {
+    pub fn empty() -> Self {
+        Self {
+            types: Default::default(),
+        }
+    }
+
+    pub fn merge(&self, other: &Generate) -> Self {
+        Self {
+            types: other.types.clone().or(self.types.clone()),
+        }
+    }
+}
+
+impl Default for Generate {
+    fn default() -> Self {
+        let types = [
+            ("feat", "Added"),
+            ("perf", "Changed"),
+            ("deprecate", "Deprecated"),
+            ("revert", "Removed"),
+            ("fix", "Fixed"),
+            ("security", "Security"),
+        ];
+        Self {
+            types: Some(
+                types
+                    .into_iter()
+                    .map(|(t, kind)| (t.to_string(), kind.to_string()))
+                    .collect(),
+            ),
         }
     }
 }
@@ -118,20 +390,101 @@ fn default_select() -> Option<HashSet<Rule>> {
     Some(HashSet::from(Rule::ALL))
 }
 
+/// Parse a TOML file, or a JSON file if the file name ends with `.json`.
+///
+/// Warn about unknown keys that start with any of `prefixes`.
+fn parse_file<T: DeserializeOwned>(path: &Path, prefixes: &[&str]) -> Result<T> {
+    let parse = || -> Result<T> {
+        let s = std::fs::read_to_string(path)?;
+        let mut unknown = Vec::new();
+        let on_unknown = |key: serde_ignored::Path| unknown.push(key.to_string());
+        let value = if path.extension().is_some_and(|ext| ext == "json") {
+            let mut deserializer = serde_json::Deserializer::from_str(&s);
+            serde_ignored::deserialize(&mut deserializer, on_unknown)?
+        } else {
+            serde_ignored::deserialize(toml::Deserializer::parse(&s)?, on_unknown)?
+        };
+        for key in unknown
+            .iter()
+            .filter(|key| prefixes.iter().any(|prefix| key.starts_with(prefix)))
+        {
+            warn(&format!("{}: unknown key `{}`", path.display(), key));
+        }
+        Ok(value)
+    };
+    parse().map_err(|e| e.in_file(path))
+}
+
+/// Print a warning once.
+fn warn(message: &str) {
+    static WARNED: Mutex<Option<HashSet<String>>> = Mutex::new(None);
+    let mut warned = WARNED.lock().unwrap();
+    if warned.get_or_insert_default().insert(message.to_string()) {
+        eprintln!("warning: {}", message);
+    }
+}
+
+fn build_glob(glob: &str) -> Result<GlobMatcher> {
+    Ok(GlobBuilder::new(glob)
+        .literal_separator(true)
+        .build()
+        .map_err(|e| Error::Glob(e.to_string()))?
+        .compile_matcher())
+}
+
+/// Remove `.` and resolve `..` components of an absolute path without accessing the file system.
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            c => normalized.push(c),
+        }
+    }
+    normalized
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    use tempfile::TempDir;
+
     #[test]
     fn test_default() {
         assert_eq!(
             Config::default(),
             Config {
+                extends: None,
                 lint: Lint {
                     select: Some(HashSet::from(Rule::ALL)),
                     ignore: Some(HashSet::new()),
                     output_format: Some(Format::Short),
-                }
+                    column_encoding: Some(Encoding::Utf8),
+                    layout: None,
+                    today: None,
+                    git: Git {
+                        enabled: Some(false),
+                        tag_pattern: Some("v{version}".to_string()),
+                        date_tolerance: Some(1),
+                        include: Some(vec![]),
+                        exclude: Some(vec![]),
+                    },
+                    overrides: vec![],
+                },
+                generate: Generate {
+                    types: Some(BTreeMap::from([
+                        ("deprecate".to_string(), "Deprecated".to_string()),
+                        ("feat".to_string(), "Added".to_string()),
+                        ("fix".to_string(), "Fixed".to_string()),
+                        ("perf".to_string(), "Changed".to_string()),
+                        ("revert".to_string(), "Removed".to_string()),
+                        ("security".to_string(), "Security".to_string()),
+                    ])),
+                },
             },
         );
     }
@@ -143,10 +496,25 @@ mod tests {
         assert_eq!(empty.merge(&default), default);
         assert_eq!(default.merge(&empty), default);
         let user = Config {
+            extends: None,
             lint: Lint {
                 select: Some(HashSet::from([Rule::MissingTitle])),
                 ignore: Some(HashSet::from([Rule::InvalidTitle])),
                 output_format: Some(Format::Json),
+                column_encoding: Some(Encoding::Utf16),
+                layout: Some(Layout::Auto),
+                today: Date::new(2025, 1, 1),
+                git: Git {
+                    enabled: Some(true),
+                    tag_pattern: Some("{version}".to_string()),
+                    date_tolerance: Some(0),
+                    include: Some(vec!["src/**".to_string()]),
+                    exclude: Some(vec![]),
+                },
+                overrides: vec![],
+            },
+            generate: Generate {
+                types: Some(BTreeMap::from([("feat".to_string(), "Added".to_string())])),
             },
         };
         assert_eq!(default.merge(&user), user);
@@ -163,18 +531,245 @@ mod tests {
             select = ["E001"]
             ignore = ["E002"]
             output_format = "json"
+            column_encoding = "utf-32"
+            layout = 2
+            today = "2025-01-01"
+
+            [lint.git]
+            enabled = true
+            tag_pattern = "release-{version}"
+            exclude = ["docs/**"]
+
+            [generate.types]
+            feat = "Added"
+            docs = "Changed"
         "#,
         )
         .unwrap();
         assert_eq!(
             config,
             Config {
+                extends: None,
                 lint: Lint {
                     select: Some(HashSet::from([Rule::MissingTitle])),
                     ignore: Some(HashSet::from([Rule::DuplicateTitle])),
                     output_format: Some(Format::Json),
-                }
+                    column_encoding: Some(Encoding::Utf32),
+                    layout: Some(Layout::Level(2)),
+                    today: Date::new(2025, 1, 1),
+                    git: Git {
+                        enabled: Some(true),
+                        tag_pattern: Some("release-{version}".to_string()),
+                        date_tolerance: None,
+                        include: None,
+                        exclude: Some(vec!["docs/**".to_string()]),
+                    },
+                    overrides: vec![],
+                },
+                generate: Generate {
+                    types: Some(BTreeMap::from([
+                        ("docs".to_string(), "Changed".to_string()),
+                        ("feat".to_string(), "Added".to_string()),
+                    ])),
+                },
             }
         );
+
+        let config = Config::from_str(
+            r#"
+            [lint]
+            layout = "auto"
+        "#,
+        )
+        .unwrap();
+        assert_eq!(config.lint.layout, Some(Layout::Auto));
+        assert!(Config::from_str("lint.layout = 5").is_err());
+        assert!(Config::from_str("lint.layout = \"foo\"").is_err());
+        assert!(Config::from_str("lint.today = \"2025-02-29\"").is_err());
+    }
+
+    #[test]
+    fn test_discover() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join(".git")).unwrap();
+        std::fs::create_dir_all(root.join("packages/a")).unwrap();
+        std::fs::create_dir_all(root.join("shared")).unwrap();
+        std::fs::write(
+            root.join("shared/nb.toml"),
+            "[lint]\nignore = [\"E001\"]\noutput_format = \"json\"\n",
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("nb.toml"),
+            "extends = \"shared/nb.toml\"\n[lint]\nselect = [\"E001\", \"E002\", \"E003\"]\n",
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("pyproject.toml"),
+            "[tool.nb.lint]\noutput_format = \"full\"\n",
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("packages/a/nb.toml"),
+            "[lint]\nignore = [\"E002\"]\n",
+        )
+        .unwrap();
+
+        let config = Config::discover(root).unwrap();
+        assert_eq!(config.lint.output_format, Some(Format::Json));
+        assert_eq!(
+            config.lint.rules(&root.join("CHANGELOG.md")).unwrap(),
+            HashSet::from([Rule::DuplicateTitle, Rule::MissingUnreleased])
+        );
+
+        let config = Config::discover(&root.join("packages/a")).unwrap();
+        assert_eq!(
+            config
+                .lint
+                .rules(&root.join("packages/a/CHANGELOG.md"))
+                .unwrap(),
+            HashSet::from([Rule::MissingTitle, Rule::MissingUnreleased])
+        );
+    }
+
+    #[test]
+    fn test_from_file_error() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("nb.toml");
+        std::fs::write(&path, "[lint]\nselect = [\"E999\"]\n").unwrap();
+        let error = Config::from_file(&path).unwrap_err().to_string();
+        assert!(error.starts_with(&format!("{}: ", path.display())));
+        assert!(error.contains("line 2, column 11"));
+        assert!(error.contains("unknown variant `E999`"));
+        assert!(matches!(
+            Config::from_file(&dir.path().join("missing.toml")),
+            Err(Error::File(_, e)) if matches!(*e, Error::Io(_))
+        ));
+    }
+
+    #[test]
+    fn test_discover_manifests() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join(".git")).unwrap();
+        std::fs::create_dir_all(root.join("crates/a")).unwrap();
+        std::fs::create_dir_all(root.join("js")).unwrap();
+        std::fs::write(
+            root.join("Cargo.toml"),
+            r#"
+            [workspace]
+            members = ["crates/*"]
+
+            [workspace.metadata.nb.lint]
+            select = ["E001", "E002"]
+            output_format = "json"
+            "#,
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("crates/a/Cargo.toml"),
+            r#"
+            [package]
+            name = "a"
+
+            [package.metadata.nb.lint]
+            ignore = ["E001"]
+            "#,
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("js/package.json"),
+            r#"{"name": "js", "nb": {"lint": {"select": ["E003"], "output_format": "full"}}}"#,
+        )
+        .unwrap();
+        std::fs::write(root.join("js/nb.toml"), "lint.output_format = \"short\"").unwrap();
+
+        let config = Config::discover(root).unwrap();
+        assert_eq!(config.lint.output_format, Some(Format::Json));
+        assert_eq!(
+            config.lint.select,
+            Some(HashSet::from([Rule::MissingTitle, Rule::DuplicateTitle]))
+        );
+
+        let config = Config::discover(&root.join("crates/a")).unwrap();
+        assert_eq!(
+            config
+                .lint
+                .rules(&root.join("crates/a/CHANGELOG.md"))
+                .unwrap(),
+            HashSet::from([Rule::DuplicateTitle])
+        );
+
+        let config = Config::discover(&root.join("js")).unwrap();
+        assert_eq!(config.lint.output_format, Some(Format::Short));
+        assert_eq!(
+            config.lint.select,
+            Some(HashSet::from([Rule::MissingUnreleased]))
+        );
+
+        std::fs::write(root.join("js/package.json"), r#"{"nb": {"lint": []}}"#).unwrap();
+        assert!(matches!(
+            Config::discover(&root.join("js")),
+            Err(Error::File(_, e)) if matches!(*e, Error::Json(_))
+        ));
+    }
+
+    #[test]
+    fn test_circular_extends() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(dir.path().join("a.toml"), "extends = \"b.toml\"").unwrap();
+        std::fs::write(dir.path().join("b.toml"), "extends = \"./a.toml\"").unwrap();
+        assert!(matches!(
+            Config::from_file(&dir.path().join("a.toml")),
+            Err(Error::Config(_))
+        ));
+    }
+
+    #[test]
+    fn test_overrides() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        let path = root.join("nb.toml");
+        std::fs::write(
+            &path,
+            r#"
+            [lint]
+            select = ["E001", "E002"]
+
+            [[lint.overrides]]
+            files = ["packages/legacy/**"]
+            ignore = ["E001"]
+
+            [[lint.overrides]]
+            files = ["packages/*/HISTORY.md"]
+            select = ["E003"]
+            "#,
+        )
+        .unwrap();
+        let config = Config::from_file(&path).unwrap();
+        let rules = |path: &str| config.lint.rules(&root.join(path)).unwrap();
+        assert_eq!(
+            rules("CHANGELOG.md"),
+            HashSet::from([Rule::MissingTitle, Rule::DuplicateTitle])
+        );
+        assert_eq!(
+            rules("packages/legacy/CHANGELOG.md"),
+            HashSet::from([Rule::DuplicateTitle])
+        );
+        assert_eq!(
+            rules("packages/legacy/HISTORY.md"),
+            HashSet::from([Rule::MissingUnreleased])
+        );
+        assert_eq!(
+            rules("packages/legacy/docs/HISTORY.md"),
+            HashSet::from([Rule::DuplicateTitle])
+        );
+        assert!(Config::from_str("[[lint.overrides]]\nfiles = [\"[\"]").is_ok());
+        std::fs::write(&path, "[[lint.overrides]]\nfiles = [\"[\"]").unwrap();
+        assert!(matches!(
+            Config::from_file(&path),
+            Err(Error::File(_, e)) if matches!(*e, Error::Glob(_))
+        ));
     }
 }
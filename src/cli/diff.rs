@@ -0,0 +1,245 @@
+//! Parse unified diffs.
+use std::path::Path;
+
+use crate::span::{Locator, Span};
+
+/// A hunk of a unified diff.
+struct Hunk<'a> {
+    /// The first line of the hunk in the new file, or the line after a deletion, from 1.
+    start: usize,
+    /// The added, deleted, and context lines, with their prefixes.
+    lines: Vec<&'a str>,
+}
+
+/// Return the hunks of the file at `path` in a unified diff. The diff may contain other files,
+/// which are ignored.
+fn hunks<'a>(diff: &'a str, path: &Path) -> Vec<Hunk<'a>> {
+    let mut hunks: Vec<Hunk> = Vec::new();
+    let mut in_file = false;
+    // The number of old and new lines left in the current hunk.
+    let mut remaining: (usize, usize) = (0, 0);
+    for diff_line in diff.lines() {
+        if remaining != (0, 0) {
+            // Lines in a hunk may look like headers, e.g. a deleted `-- ` line.
+            let (old, new) = remaining;
+            remaining = match diff_line.chars().next() {
+                Some('+') => (old, new.saturating_sub(1)),
+                Some('-') => (old.saturating_sub(1), new),
+                Some('\\') => continue,
+                _ => (old.saturating_sub(1), new.saturating_sub(1)),
+            };
+            if in_file && let Some(hunk) = hunks.last_mut() {
+                hunk.lines.push(diff_line);
+            }
+        } else if let Some(target) = diff_line.strip_prefix("+++ ") {
+            in_file = is_target(target, path);
+        } else if diff_line.starts_with("--- ") || diff_line.starts_with("diff ") {
+            in_file = false;
+        } else if let Some(header) = diff_line.strip_prefix("@@ ")
+            && let Some((start, old, new)) = parse_hunk_header(header)
+        {
+            remaining = (old, new);
+            if in_file {
+                hunks.push(Hunk {
+                    start,
+                    lines: Vec::new(),
+                });
+            }
+        }
+    }
+    hunks
+}
+
+/// Return the spans of changed lines in `content` from a unified diff of the file at `path`.
+///
+/// Added lines are spans of the line. Deleted lines are empty spans at the start of the
+/// following line, once per hunk. The diff may contain other files, which are ignored.
+pub fn changes(diff: &str, path: &Path, content: &str) -> Vec<Span> {
+    let locator = Locator::new(content);
+    let line_start = |line: usize| {
+        locator
+            .line_span(line)
+            .map_or(content.len(), |span| span.start)
+    };
+    let mut changes = Vec::new();
+    for hunk in hunks(diff, path) {
+        let mut line = hunk.start;
+        for diff_line in hunk.lines {
+            if diff_line.starts_with('+') {
+                if let Some(span) = locator.line_span(line) {
+                    changes.push(span);
+                }
+                line += 1;
+            } else if diff_line.starts_with('-') {
+                let start = line_start(line);
+                changes.push(Span::new(start, start));
+            } else {
+                line += 1;
+            }
+        }
+    }
+    changes.dedup();
+    changes
+}
+
+/// Return the content of the file at `path` before a unified diff, from its content after.
+///
+/// If the diff does not contain the file, the content is unchanged.
+pub fn original(diff: &str, path: &Path, content: &str) -> String {
+    let lines: Vec<&str> = content.split_inclusive('\n').collect();
+    let mut original = String::new();
+    // The index of the next line of `content` to copy.
+    let mut next = 0;
+    for hunk in hunks(diff, path) {
+        let first = hunk.start.saturating_sub(1).clamp(next, lines.len());
+        original.extend(lines[next..first].iter().copied());
+        next = first;
+        for diff_line in hunk.lines {
+            if diff_line.starts_with('+') {
+                next += 1;
+            } else if let Some(deleted) = diff_line.strip_prefix('-') {
+                original.push_str(deleted);
+                original.push('\n');
+            } else {
+                if let Some(line) = lines.get(next) {
+                    original.push_str(line);
+                }
+                next += 1;
+            }
+        }
+    }
+    original.extend(lines.get(next..).unwrap_or_default().iter().copied());
+    original
+}
+
+/// Return `true` if the `+++` target of a diff refers to `path`.
+fn is_target(target: &str, path: &Path) -> bool {
+    // Strip the timestamp some tools append after a tab.
+    let target = target.split('\t').next().unwrap_or_default();
+    if target == "/dev/null" {
+        return false;
+    }
+    let target = Path::new(target.strip_prefix("b/").unwrap_or(target));
+    path.ends_with(target) || target.ends_with(path)
+}
+
+/// Parse a hunk header (`-1,2 +3,4 @@`) into the start line of the new file and the numbers of
+/// old and new lines.
+fn parse_hunk_header(header: &str) -> Option<(usize, usize, usize)> {
+    let range = |prefix: char| -> Option<(usize, usize)> {
+        let range = header
+            .split_whitespace()
+            .find_map(|s| s.strip_prefix(prefix))?;
+        let start = range.split(',').next()?.parse().ok()?;
+        let count = match range.split_once(',') {
+            Some((_, count)) => count.parse().ok()?,
+            None => 1,
+        };
+        Some((start, count))
+    };
+    let (_, old) = range('-')?;
+    let (start, new) = range('+')?;
+    // Hunks that only delete lines start at the line before the deletion.
+    Some((if new == 0 { start + 1 } else { start }, old, new))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::span::Ranged;
+
+    #[test]
+    fn test_changes() {
+        let content = "# Changelog\n\n## [Unreleased]\n\n### Added\n\n* Foo\n\n## [1.0.0]\n";
+        let diff = "\
+diff --git a/README.md b/README.md
+--- a/README.md
++++ b/README.md
+@@ -1 +1 @@
+-Foo
++Bar
+diff --git a/CHANGELOG.md b/CHANGELOG.md
+index 0000000..1111111 100644
+--- a/CHANGELOG.md
++++ b/CHANGELOG.md
+@@ -4,0 +5,3 @@
++### Added
++
++* Foo
+@@ -8,2 +10,0 @@
+-* Bar
+-
+";
+        let changes = changes(diff, Path::new("./CHANGELOG.md"), content);
+        let changed: Vec<&str> = changes.iter().map(|s| &content[s.range()]).collect();
+        assert_eq!(changed, vec!["### Added", "", "* Foo", ""]);
+        assert_eq!(changes[3], Span::new(content.len(), content.len()));
+    }
+
+    #[test]
+    fn test_changes_with_context() {
+        let content = "a\nb\nc\nd\n";
+        let diff = "\
+--- CHANGELOG.md\t2025-01-01 00:00:00
++++ CHANGELOG.md\t2025-01-02 00:00:00
+@@ -1,4 +1,4 @@
+ a
+-x
++b
+ c
+-y
+ d
+";
+        let changes = changes(diff, Path::new("CHANGELOG.md"), content);
+        assert_eq!(
+            changes,
+            vec![Span::new(2, 2), Span::new(2, 3), Span::new(6, 6),]
+        );
+    }
+
+    #[test]
+    fn test_changes_like_headers() {
+        // Deleted and added lines that look like file headers.
+        let content = "a\n++ b\nc\nd\n";
+        let diff = "\
+--- a/CHANGELOG.md
++++ b/CHANGELOG.md
+@@ -2 +2 @@
+--- b
++++ b
+@@ -3,0 +4 @@
++d
+";
+        let changes = changes(diff, Path::new("CHANGELOG.md"), content);
+        assert_eq!(
+            changes,
+            vec![Span::new(2, 2), Span::new(2, 6), Span::new(9, 10)]
+        );
+    }
+
+    #[test]
+    fn test_original() {
+        let content = "# Changelog\n\n## [1.1.0]\n\n* Foo\n\n## [1.0.0]\n\n* Bar\n";
+        let diff = "\
+diff --git a/CHANGELOG.md b/CHANGELOG.md
+--- a/CHANGELOG.md
++++ b/CHANGELOG.md
+@@ -3 +3 @@
+-## [Unreleased]
++## [1.1.0]
+@@ -7,0 +8,2 @@
++
++* Bar
+@@ -9 +10,0 @@
+-* Baz
+";
+        assert_eq!(
+            original(diff, Path::new("CHANGELOG.md"), content),
+            "# Changelog\n\n## [Unreleased]\n\n* Foo\n\n## [1.0.0]\n* Baz\n"
+        );
+        assert_eq!(original("", Path::new("CHANGELOG.md"), content), content);
+        let diff = "--- /dev/null\n+++ b/CHANGELOG.md\n@@ -0,0 +1,2 @@\n+a\n+b\n";
+        assert_eq!(original(diff, Path::new("CHANGELOG.md"), "a\nb\n"), "");
+    }
+}
@@ -1,6 +1,9 @@
 use std::fmt;
 use std::io::Error as IoError;
+use std::path::PathBuf;
+use std::process::ExitCode;
 
+use serde_json::Error as JsonError;
 use toml::de::Error as TomlDeError;
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -9,16 +12,59 @@ pub type Result<T> = std::result::Result<T, Error>;
 pub enum Error {
     Io(IoError),
     Toml(TomlDeError),
+    Json(JsonError),
+    SectionNotFound(String),
+    Git(String),
+    Glob(String),
+    Config(String),
+    Usage(String),
+    Server(String),
+    /// An error in a file.
+    File(PathBuf, Box<Error>),
+    /// Errors in this many files, which were already reported.
+    Failed(usize),
     Check,
 }
 
+impl Error {
+    /// Add the path of the file that caused this error.
+    pub fn in_file<P: Into<PathBuf>>(self, path: P) -> Self {
+        Self::File(path.into(), Box::new(self))
+    }
+
+    /// Return `true` if this error means that checks failed.
+    pub fn is_check(&self) -> bool {
+        matches!(self, Self::Check)
+    }
+
+    /// Return the exit code for this error.
+    ///
+    /// Failed checks exit with status 1. Usage, configuration, and I/O errors exit with status 2.
+    pub fn exit_code(&self) -> ExitCode {
+        match self {
+            Self::Check => ExitCode::from(1),
+            _ => ExitCode::from(2),
+        }
+    }
+}
+
 impl std::error::Error for Error {}
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Self::Io(e) => write!(f, "I/O error: {}", e),
-            Self::Toml(e) => write!(f, "TOML parse error: {}", e),
+            Self::Toml(e) => write!(f, "{}", e.to_string().trim_end()),
+            Self::Json(e) => write!(f, "JSON error: {}", e),
+            Self::SectionNotFound(section) => write!(f, "section not found: {}", section),
+            Self::Git(e) => write!(f, "git error: {}", e),
+            Self::Glob(e) => write!(f, "invalid glob: {}", e),
+            Self::Config(e) => write!(f, "configuration error: {}", e),
+            Self::Usage(e) => write!(f, "{}", e),
+            Self::Server(e) => write!(f, "language server error: {}", e),
+            Self::File(path, e) => write!(f, "{}: {}", path.display(), e),
+            Self::Failed(1) => write!(f, "could not lint 1 file"),
+            Self::Failed(n) => write!(f, "could not lint {} files", n),
             Self::Check => write!(f, "checks failed"),
         }
     }
@@ -35,3 +81,9 @@ impl From<TomlDeError> for Error {
         Self::Toml(err)
     }
 }
+
+impl From<JsonError> for Error {
+    fn from(err: JsonError) -> Self {
+        Self::Json(err)
+    }
+}
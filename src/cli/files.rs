@@ -0,0 +1,155 @@
+//! Find changelogs to lint.
+use std::path::{Component, Path, PathBuf};
+
+use globset::{GlobBuilder, GlobMatcher};
+use ignore::WalkBuilder;
+
+use super::error::{Error, Result};
+
+/// The names of changelog files to find in directories.
+pub const CHANGELOG_NAMES: [&str; 3] = ["CHANGELOG.md", "CHANGES.md", "HISTORY.md"];
+
+/// Find the files to lint from a list of files, directories, and glob patterns.
+///
+/// Directories are searched recursively for files named [`CHANGELOG_NAMES`]. Searches respect
+/// `.gitignore` files. Paths that do not exist and are not glob patterns are returned as-is.
+pub fn find(paths: &[PathBuf]) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for path in paths {
+        if path.is_dir() {
+            files.extend(walk(path, is_changelog)?);
+        } else if !path.exists()
+            && let Some(pattern) = path.to_str().filter(|s| is_glob(s))
+        {
+            let matcher = GlobBuilder::new(normalize(Path::new(pattern)).to_str().unwrap())
+                .literal_separator(true)
+                .build()
+                .map_err(|e| Error::Glob(e.to_string()))?
+                .compile_matcher();
+            files.extend(walk(&glob_base(pattern), |p| matches(&matcher, p))?);
+        } else {
+            files.push(path.clone());
+        }
+    }
+    let mut seen = std::collections::HashSet::new();
+    files.retain(|file| seen.insert(file.clone()));
+    Ok(files)
+}
+
+fn walk<F: Fn(&Path) -> bool>(dir: &Path, filter: F) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for entry in WalkBuilder::new(dir)
+        .sort_by_file_path(|a, b| a.cmp(b))
+        .build()
+    {
+        let entry = entry.map_err(|e| Error::Io(std::io::Error::other(e)))?;
+        if entry.file_type().is_some_and(|t| t.is_file()) && filter(entry.path()) {
+            files.push(normalize(entry.path()));
+        }
+    }
+    Ok(files)
+}
+
+fn is_changelog(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| CHANGELOG_NAMES.contains(&name))
+}
+
+fn is_glob(s: &str) -> bool {
+    s.contains(['*', '?', '[', '{'])
+}
+
+fn matches(matcher: &GlobMatcher, path: &Path) -> bool {
+    matcher.is_match(normalize(path))
+}
+
+/// Return the directory to search for a glob pattern: the longest prefix without glob syntax.
+fn glob_base(pattern: &str) -> PathBuf {
+    let base: PathBuf = Path::new(pattern)
+        .components()
+        .take_while(|c| !c.as_os_str().to_str().is_some_and(is_glob))
+        .collect();
+    if base.as_os_str().is_empty() {
+        PathBuf::from(".")
+    } else {
+        base
+    }
+}
+
+/// Remove `.` components from a path.
+fn normalize(path: &Path) -> PathBuf {
+    path.components()
+        .filter(|c| *c != Component::CurDir)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use tempfile::TempDir;
+
+    fn touch(dir: &Path, path: &str) {
+        let path = dir.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, "").unwrap();
+    }
+
+    #[test]
+    fn test_find() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        // `ignore` only reads `.gitignore` files in git repositories.
+        std::fs::create_dir(root.join(".git")).unwrap();
+        std::fs::write(root.join(".gitignore"), "target/\n").unwrap();
+        for path in [
+            "CHANGELOG.md",
+            "README.md",
+            "packages/a/CHANGES.md",
+            "packages/b/HISTORY.md",
+            "packages/b/docs/CHANGELOG.md",
+            "target/CHANGELOG.md",
+        ] {
+            touch(root, path);
+        }
+        let relative = |files: Vec<PathBuf>| -> Vec<String> {
+            files
+                .iter()
+                .map(|f| f.strip_prefix(root).unwrap().to_string_lossy().into_owned())
+                .collect()
+        };
+
+        assert_eq!(
+            relative(find(&[root.to_path_buf()]).unwrap()),
+            vec![
+                "CHANGELOG.md",
+                "packages/a/CHANGES.md",
+                "packages/b/HISTORY.md",
+                "packages/b/docs/CHANGELOG.md",
+            ]
+        );
+        assert_eq!(
+            relative(find(&[root.join("packages/*/*.md")]).unwrap()),
+            vec!["packages/a/CHANGES.md", "packages/b/HISTORY.md"]
+        );
+        assert_eq!(
+            relative(find(&[root.join("**/CHANGELOG.md"), root.join("CHANGELOG.md")]).unwrap()),
+            vec!["CHANGELOG.md", "packages/b/docs/CHANGELOG.md"]
+        );
+        assert_eq!(
+            find(&[PathBuf::from("missing.md")]).unwrap(),
+            vec![PathBuf::from("missing.md")]
+        );
+    }
+
+    #[test]
+    fn test_glob_base() {
+        assert_eq!(
+            glob_base("packages/*/CHANGELOG.md"),
+            PathBuf::from("packages")
+        );
+        assert_eq!(glob_base("**/CHANGELOG.md"), PathBuf::from("."));
+        assert_eq!(glob_base("./docs/*.md"), PathBuf::from("./docs"));
+    }
+}
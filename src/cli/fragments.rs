@@ -0,0 +1,302 @@
+//! Changelog fragments.
+//!
+//! A fragment is a Markdown file in a `changelog.d` directory next to the changelog. Each
+//! fragment describes changes of one change type, either in its file name (`1234.fixed.md`) or in
+//! front matter:
+//!
+//! ```markdown
+//! ---
+//! type: fixed
+//! ---
+//!
+//! Fix the thing.
+//! ```
+//!
+//! The body is a paragraph or a list. Each paragraph or list item becomes a change.
+use std::path::{Path, PathBuf};
+
+use pulldown_cmark::{Event, Parser, Tag, TagEnd};
+
+use crate::diagnostic::Diagnostic;
+use crate::linter::CHANGE_TYPES;
+use crate::rule::Rule;
+use crate::ruleset::RuleSet;
+use crate::span::{Ranged, Span};
+
+use super::error::Result;
+
+/// The name of the fragment directory.
+pub const DIRECTORY: &str = "changelog.d";
+
+/// A parsed fragment.
+#[derive(Debug, PartialEq)]
+pub struct Fragment<'a> {
+    pub source: &'a str,
+    /// The change type as written, from the front matter or the file name.
+    pub kind: Option<&'a str>,
+    /// The span of the change type in the front matter, if any.
+    pub kind_span: Option<Span>,
+    /// The span of the body after the front matter.
+    pub body_span: Span,
+}
+
+impl<'a> Fragment<'a> {
+    /// Parse a fragment from its path and content.
+    pub fn parse(path: &'a Path, source: &'a str) -> Self {
+        let mut fragment = Self {
+            source,
+            kind: path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .and_then(|stem| stem.rsplit_once('.'))
+                .map(|(_, kind)| kind),
+            kind_span: None,
+            body_span: Span::new(0, source.len()),
+        };
+        let Some(rest) = source.strip_prefix("---\n") else {
+            return fragment;
+        };
+        // `end` is the offset of the closing `---` in `rest`.
+        let end = if rest.starts_with("---\n") || rest == "---" {
+            Some(0)
+        } else {
+            rest.find("\n---\n")
+                .map(|i| i + 1)
+                .or_else(|| rest.ends_with("\n---").then(|| rest.len() - 3))
+        };
+        let Some(end) = end else {
+            return fragment;
+        };
+        let mut offset = 4;
+        for line in rest[..end].lines() {
+            if let Some((key, value)) = line.split_once(':')
+                && key.trim() == "type"
+            {
+                let value = value.trim();
+                let start = offset + line.find(value).unwrap_or(key.len() + 1);
+                fragment.kind = Some(value);
+                fragment.kind_span = Some(Span::new(start, start + value.len()));
+            }
+            offset += line.len() + 1;
+        }
+        let body_start = (4 + end + 4).min(source.len());
+        fragment.body_span = Span::new(body_start, source.len());
+        fragment
+    }
+
+    /// Return the change type, e.g. `Fixed`, if it is valid.
+    pub fn change_type(&self) -> Option<&'static str> {
+        let kind = self.kind?;
+        CHANGE_TYPES
+            .iter()
+            .find(|t| t.eq_ignore_ascii_case(kind))
+            .copied()
+    }
+
+    /// Return the changes in the body: each top-level paragraph and list item.
+    pub fn items(&self) -> Vec<String> {
+        self.blocks()
+            .into_iter()
+            .filter_map(|(block, span)| {
+                let text = &self.source[span.range()];
+                match block {
+                    Block::Paragraph => Some(text.trim().to_string()),
+                    Block::Item => {
+                        let text = text.trim_start();
+                        let text = text
+                            .strip_prefix(['*', '-', '+'])
+                            .unwrap_or(text)
+                            .trim_start();
+                        Some(text.trim_end().to_string())
+                    }
+                    Block::Other => None,
+                }
+            })
+            .filter(|item| !item.is_empty())
+            .map(|item| {
+                // Indent continuation lines to keep them in the list item.
+                item.lines().map(str::trim).collect::<Vec<_>>().join("\n  ")
+            })
+            .collect()
+    }
+
+    /// Return the top-level blocks of the body, with list items instead of lists.
+    fn blocks(&self) -> Vec<(Block, Span)> {
+        let body = &self.source[self.body_span.range()];
+        let mut blocks = Vec::new();
+        let mut depth = 0;
+        let mut lists = 0;
+        for (event, range) in Parser::new(body).into_offset_iter() {
+            let span = Span::new(
+                self.body_span.start + range.start,
+                self.body_span.start + range.end,
+            );
+            match event {
+                Event::Start(tag) => {
+                    if depth == 0 || (depth == 1 && lists == 1) {
+                        let block = match tag {
+                            Tag::Paragraph if depth == 0 => Some(Block::Paragraph),
+                            Tag::List(_) if depth == 0 => None,
+                            Tag::Item => Some(Block::Item),
+                            _ => Some(Block::Other),
+                        };
+                        if let Some(block) = block {
+                            blocks.push((block, span));
+                        }
+                    }
+                    if let Tag::List(_) = tag {
+                        lists += 1;
+                    }
+                    depth += 1;
+                }
+                Event::End(tag) => {
+                    if let TagEnd::List(_) = tag {
+                        lists -= 1;
+                    }
+                    depth -= 1;
+                }
+                Event::Html(_) | Event::Rule if depth == 0 => blocks.push((Block::Other, span)),
+                _ => {}
+            }
+        }
+        blocks
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Block {
+    Paragraph,
+    Item,
+    Other,
+}
+
+/// Return the fragment files in the `changelog.d` directory in `dir`, sorted by name.
+///
+/// Fragments are Markdown files other than `README.md`.
+pub fn find(dir: &Path) -> Result<Vec<PathBuf>> {
+    let dir = dir.join(DIRECTORY);
+    if !dir.is_dir() {
+        return Ok(vec![]);
+    }
+    let mut paths = Vec::new();
+    for entry in std::fs::read_dir(&dir)? {
+        let path = entry?.path();
+        if path.is_file()
+            && path.extension().is_some_and(|ext| ext == "md")
+            && path
+                .file_name()
+                .is_some_and(|name| !name.eq_ignore_ascii_case("README.md"))
+        {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+    Ok(paths)
+}
+
+/// Check a fragment with the rules that apply to a section of changes.
+pub fn lint(fragment: &Fragment, ruleset: &RuleSet) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    match fragment.kind {
+        None => diagnostics.push(Diagnostic::new(Rule::MissingChangeType, None)),
+        Some(kind) if fragment.change_type().is_none() => {
+            // The type in a file name is not in the source, so pass it as the argument.
+            diagnostics.push(match fragment.kind_span {
+                Some(span) => Diagnostic::new(Rule::UnknownChangeType, Some(span)),
+                None => Diagnostic::new(Rule::UnknownChangeType, None).with_argument(kind),
+            });
+        }
+        Some(_) => {}
+    }
+    let blocks = fragment.blocks();
+    if !blocks.iter().any(|(block, _)| *block != Block::Other) {
+        diagnostics.push(Diagnostic::new(Rule::EmptySection, None));
+    }
+    for (block, span) in blocks {
+        if block == Block::Other {
+            diagnostics.push(Diagnostic::new(Rule::UnexpectedContent, Some(span)));
+        }
+    }
+    diagnostics.retain(|d| ruleset.is_enabled(d.rule));
+    diagnostics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::HashSet;
+
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse() {
+        let path = Path::new("changelog.d/1234.fixed.md");
+        let fragment = Fragment::parse(path, "Fix foo.\n");
+        assert_eq!(fragment.kind, Some("fixed"));
+        assert_eq!(fragment.change_type(), Some("Fixed"));
+        assert_eq!(fragment.kind_span, None);
+        assert_eq!(fragment.items(), vec!["Fix foo."]);
+
+        let source = "---\ntype: Added\n---\n\n* Add foo\n* Add bar\n  baz\n\nAdd qux\nquux\n";
+        let fragment = Fragment::parse(Path::new("foo.md"), source);
+        assert_eq!(fragment.kind, Some("Added"));
+        assert_eq!(&source[fragment.kind_span.unwrap().range()], "Added");
+        assert_eq!(fragment.body_span, Span::new(20, source.len()));
+        assert_eq!(
+            fragment.items(),
+            vec!["Add foo", "Add bar\n  baz", "Add qux\n  quux"]
+        );
+
+        let fragment = Fragment::parse(Path::new("foo.md"), "Foo\n");
+        assert_eq!(fragment.kind, None);
+        assert_eq!(fragment.change_type(), None);
+    }
+
+    #[test]
+    fn test_lint() {
+        let ruleset = RuleSet::default();
+        let lint = |path: &str, source: &str| {
+            let fragment = Fragment::parse(Path::new(path), source);
+            lint(&fragment, &ruleset)
+                .into_iter()
+                .map(|d| {
+                    let text = d.location.map(|s| source[s.range()].to_string());
+                    (d.code().to_string(), d.message(source), text)
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(lint("1.fixed.md", "* Fix foo\n"), vec![]);
+        insta::assert_yaml_snapshot!(vec![
+            lint("1.md", "Foo\n"),
+            lint("1.features.md", "Foo\n"),
+            lint("1.md", "---\ntype: features\n---\nFoo\n"),
+            lint("1.added.md", ""),
+            lint("1.added.md", "# Foo\n\n* Foo\n\n```\nbar\n```\n"),
+        ]);
+        let ruleset = RuleSet::new(HashSet::from([Rule::EmptySection]));
+        let fragment = Fragment::parse(Path::new("1.md"), "Foo\n");
+        assert_eq!(super::lint(&fragment, &ruleset), vec![]);
+    }
+
+    #[test]
+    fn test_find() {
+        let dir = TempDir::new().unwrap();
+        assert!(find(dir.path()).unwrap().is_empty());
+        let fragments = dir.path().join(DIRECTORY);
+        std::fs::create_dir(&fragments).unwrap();
+        for name in [
+            "2.fixed.md",
+            "1.added.md",
+            "README.md",
+            ".gitkeep",
+            "notes.txt",
+        ] {
+            std::fs::write(fragments.join(name), "").unwrap();
+        }
+        assert_eq!(
+            find(dir.path()).unwrap(),
+            vec![fragments.join("1.added.md"), fragments.join("2.fixed.md")]
+        );
+    }
+}
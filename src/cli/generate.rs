@@ -0,0 +1,237 @@
+//! Generate changelog sections from commits in Conventional Commits format.
+//!
+//! See <https://www.conventionalcommits.org/en/v1.0.0/>.
+use std::collections::BTreeMap;
+
+use crate::Tag;
+use crate::changelog::owned::OwnedChanges;
+use crate::linter::CHANGE_TYPES;
+
+use super::error::{Error, Result};
+use super::git::Commit;
+
+/// The prefix of changes from breaking commits.
+const BREAKING: &str = "**Breaking:** ";
+
+/// A commit message in Conventional Commits format.
+#[derive(Debug, PartialEq)]
+struct ConventionalCommit<'a> {
+    kind: &'a str,
+    scope: Option<&'a str>,
+    breaking: bool,
+    description: &'a str,
+}
+
+impl<'a> ConventionalCommit<'a> {
+    /// Parse a commit message like `feat(parser)!: add foo`.
+    ///
+    /// Return `None` if the subject is not in Conventional Commits format.
+    fn parse(subject: &'a str, body: &str) -> Option<Self> {
+        let (prefix, description) = subject.split_once(':')?;
+        let description = description.trim();
+        let (prefix, mut breaking) = match prefix.strip_suffix('!') {
+            Some(prefix) => (prefix, true),
+            None => (prefix, false),
+        };
+        let (kind, scope) = match prefix.split_once('(') {
+            Some((kind, scope)) => (kind, Some(scope.strip_suffix(')')?)),
+            None => (prefix, None),
+        };
+        if kind.is_empty()
+            || !kind.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+            || description.is_empty()
+        {
+            return None;
+        }
+        breaking |= body.lines().any(|line| {
+            line.starts_with("BREAKING CHANGE:") || line.starts_with("BREAKING-CHANGE:")
+        });
+        Some(Self {
+            kind,
+            scope,
+            breaking,
+            description,
+        })
+    }
+}
+
+/// Check that the change types in `types` are valid.
+pub fn validate(types: &BTreeMap<String, String>) -> Result<()> {
+    for (kind, change_type) in types {
+        if !CHANGE_TYPES.contains(&change_type.as_str()) {
+            return Err(Error::Config(format!(
+                "invalid change type `{}` for commit type `{}` (expected one of: {})",
+                change_type,
+                kind,
+                CHANGE_TYPES.join(", ")
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Group commits into change sections, in the order of [`CHANGE_TYPES`].
+///
+/// `types` maps Conventional Commits types to change types. Breaking changes are always
+/// `Changed`. Other commits are skipped.
+pub fn changes(commits: &[Commit], types: &BTreeMap<String, String>) -> Vec<OwnedChanges> {
+    let mut sections: Vec<OwnedChanges> = Vec::new();
+    for commit in commits {
+        let Some(message) = ConventionalCommit::parse(&commit.subject, &commit.body) else {
+            continue;
+        };
+        let change_type = if message.breaking {
+            "Changed"
+        } else {
+            match types.get(&message.kind.to_lowercase()) {
+                Some(change_type) => change_type.as_str(),
+                None => continue,
+            }
+        };
+        let mut item = String::new();
+        if message.breaking {
+            item.push_str(BREAKING);
+        }
+        if let Some(scope) = message.scope {
+            item.push_str(scope);
+            item.push_str(": ");
+        }
+        item.push_str(&capitalize(message.description));
+        match sections.iter_mut().find(|s| s.kind == change_type) {
+            Some(section) => section.items.push(item),
+            None => sections.push(OwnedChanges {
+                kind: change_type.to_string(),
+                items: vec![item],
+            }),
+        }
+    }
+    sections.sort_by_key(|s| CHANGE_TYPES.iter().position(|t| *t == s.kind));
+    sections
+}
+
+/// Split commits, newest first, into unreleased commits and the commits of each release in
+/// `tags`.
+///
+/// A release includes the commit its tag points to and the older commits up to the next release.
+pub fn split<'a>(
+    commits: &'a [Commit],
+    tags: &'a [Tag],
+) -> (&'a [Commit], Vec<(&'a Tag, &'a [Commit])>) {
+    let starts: Vec<(usize, &Tag)> = commits
+        .iter()
+        .enumerate()
+        .filter_map(|(i, commit)| {
+            let tag = tags.iter().find(|tag| commit.tags.contains(&tag.name))?;
+            Some((i, tag))
+        })
+        .collect();
+    let unreleased = &commits[..starts.first().map_or(commits.len(), |(i, _)| *i)];
+    let releases = starts
+        .iter()
+        .enumerate()
+        .map(|(n, (i, tag))| {
+            let end = starts.get(n + 1).map_or(commits.len(), |(j, _)| *j);
+            (*tag, &commits[*i..end])
+        })
+        .collect();
+    (unreleased, releases)
+}
+
+fn capitalize(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(c) => c.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::cli::config::Generate;
+
+    fn commit(subject: &str, body: &str, tags: &[&str]) -> Commit {
+        Commit {
+            subject: subject.to_string(),
+            body: body.to_string(),
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            ConventionalCommit::parse("feat(parser)!: add foo", ""),
+            Some(ConventionalCommit {
+                kind: "feat",
+                scope: Some("parser"),
+                breaking: true,
+                description: "add foo",
+            })
+        );
+        assert_eq!(
+            ConventionalCommit::parse("fix: bar", "Body\n\nBREAKING CHANGE: baz"),
+            Some(ConventionalCommit {
+                kind: "fix",
+                scope: None,
+                breaking: true,
+                description: "bar",
+            })
+        );
+        assert_eq!(ConventionalCommit::parse("Update README", ""), None);
+        assert_eq!(ConventionalCommit::parse("Merge branch: foo", ""), None);
+        assert_eq!(ConventionalCommit::parse("feat(parser: foo", ""), None);
+        assert_eq!(ConventionalCommit::parse("feat: ", ""), None);
+    }
+
+    #[test]
+    fn test_changes() {
+        let commits = [
+            commit("fix(cli): handle empty files", "", &[]),
+            commit("docs: update README", "", &[]),
+            commit("feat!: drop support for foo", "", &[]),
+            commit("feat: add bar", "", &[]),
+            commit("Revert \"feat: add baz\"", "", &[]),
+            commit("revert: add baz", "", &[]),
+        ];
+        let types = Generate::default().types.unwrap();
+        insta::assert_yaml_snapshot!(
+            changes(&commits, &types)
+                .into_iter()
+                .map(|c| (c.kind, c.items))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(validate(&Generate::default().types.unwrap()).is_ok());
+        let types = BTreeMap::from([("feat".to_string(), "Features".to_string())]);
+        assert!(matches!(validate(&types), Err(Error::Config(_))));
+    }
+
+    #[test]
+    fn test_split() {
+        let commits = [
+            commit("feat: d", "", &[]),
+            commit("feat: c", "", &["v0.2.0"]),
+            commit("feat: b", "", &["other"]),
+            commit("feat: a", "", &["v0.1.0"]),
+        ];
+        let tags = [
+            Tag::new("v0.1.0", "0.1.0", None),
+            Tag::new("v0.2.0", "0.2.0", None),
+        ];
+        let (unreleased, releases) = split(&commits, &tags);
+        assert_eq!(unreleased, &commits[..1]);
+        assert_eq!(
+            releases,
+            vec![(&tags[1], &commits[1..3]), (&tags[0], &commits[3..])]
+        );
+        let (unreleased, releases) = split(&commits, &[]);
+        assert_eq!(unreleased.len(), 4);
+        assert!(releases.is_empty());
+    }
+}
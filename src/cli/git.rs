@@ -0,0 +1,420 @@
+//! Read data from a git repository by running `git`.
+use std::ffi::OsStr;
+use std::path::Path;
+use std::process::Command;
+
+use crate::date::Date;
+use crate::vcs::Tag;
+
+use super::error::{Error, Result};
+
+/// Run `git` in `dir` and return its standard output.
+pub fn git<I, S>(dir: &Path, args: I) -> Result<String>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<OsStr>,
+{
+    let output = Command::new("git").arg("-C").arg(dir).args(args).output()?;
+    if !output.status.success() {
+        return Err(Error::Git(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// A commit.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Commit {
+    pub hash: String,
+    /// The names of the tags that point to the commit.
+    pub tags: Vec<String>,
+    pub subject: String,
+    pub body: String,
+}
+
+/// Return the commits in `range`, newest first.
+pub fn commits(dir: &Path, range: &str) -> Result<Vec<Commit>> {
+    let output = git(
+        dir,
+        [
+            "log",
+            "--topo-order",
+            "--decorate-refs=refs/tags",
+            // Separate fields with US and commits with RS.
+            "--format=%H%x1f%D%x1f%s%x1f%b%x1e",
+            range,
+            "--",
+        ],
+    )?;
+    Ok(output
+        .split('\x1e')
+        .filter_map(|record| {
+            let mut fields = record.trim_start_matches('\n').split('\x1f');
+            let hash = fields.next().filter(|hash| !hash.is_empty())?;
+            let tags = fields
+                .next()?
+                .split(", ")
+                .filter_map(|r| r.strip_prefix("tag: "))
+                .map(str::to_string)
+                .collect();
+            Some(Commit {
+                hash: hash.to_string(),
+                tags,
+                subject: fields.next()?.to_string(),
+                body: fields.next()?.trim_end().to_string(),
+            })
+        })
+        .collect())
+}
+
+/// Return the names and creation dates of all tags in the repository.
+///
+/// The date of an annotated tag is the date it was tagged. The date of a lightweight tag is the
+/// date of the commit it points to.
+pub fn tags(dir: &Path) -> Result<Vec<(String, Option<Date>)>> {
+    let output = git(
+        dir,
+        [
+            "for-each-ref",
+            "--format=%(refname:short) %(creatordate:short)",
+            "refs/tags",
+        ],
+    )?;
+    Ok(output
+        .lines()
+        .filter_map(|line| {
+            let (name, date) = line.rsplit_once(' ')?;
+            Some((name.to_string(), date.parse().ok()))
+        })
+        .collect())
+}
+
+/// Return the most recent tag reachable from `HEAD` that matches `pattern`, or `None` if there is
+/// no such tag.
+pub fn latest_tag(dir: &Path, pattern: &str) -> Result<Option<String>> {
+    let output = git(
+        dir,
+        [
+            "log",
+            "--topo-order",
+            "--simplify-by-decoration",
+            "--decorate-refs=refs/tags",
+            "--format=%D",
+            "HEAD",
+            "--",
+        ],
+    )?;
+    Ok(output
+        .lines()
+        .flat_map(|line| line.split(", "))
+        .filter_map(|r| r.strip_prefix("tag: "))
+        .find(|name| Tag::from_pattern(name, pattern, None).is_some())
+        .map(str::to_string))
+}
+
+/// Return the name of the current branch, or `None` if `HEAD` is detached.
+pub fn branch(dir: &Path) -> Result<Option<String>> {
+    let name = git(dir, ["rev-parse", "--abbrev-ref", "HEAD"])?;
+    Ok(Some(name.trim())
+        .filter(|name| *name != "HEAD")
+        .map(str::to_string))
+}
+
+/// Return the web URL of the `origin` remote.
+pub fn remote_url(dir: &Path) -> Result<String> {
+    let url = git(dir, ["remote", "get-url", "origin"])?;
+    Ok(web_url(url.trim()))
+}
+
+/// Convert a git remote URL like `git@github.com:owner/repo.git` to a web URL like
+/// `https://github.com/owner/repo`.
+pub fn web_url(url: &str) -> String {
+    let url = url.trim_end_matches('/');
+    let url = url.strip_suffix(".git").unwrap_or(url);
+    if let Some((scheme, rest)) = url.split_once("://") {
+        let rest = match scheme {
+            // Drop the user and port of SSH URLs.
+            "ssh" | "git+ssh" => {
+                let rest = rest.split_once('@').map_or(rest, |(_, host)| host);
+                match rest.split_once('/') {
+                    Some((host, path)) => {
+                        let host = host.split_once(':').map_or(host, |(host, _)| host);
+                        format!("{}/{}", host, path)
+                    }
+                    None => rest.to_string(),
+                }
+            }
+            _ => rest.to_string(),
+        };
+        return format!("https://{}", rest);
+    }
+    // SCP-like syntax: `user@host:path`.
+    if let Some((host, path)) = url.split_once(':') {
+        let host = host.split_once('@').map_or(host, |(_, host)| host);
+        return format!("https://{}/{}", host, path);
+    }
+    url.to_string()
+}
+
+/// Count the commits since `since` that touch paths matching the `include` globs, excluding
+/// paths that match the `exclude` globs.
+///
+/// Globs are relative to the repository root. If `include` is empty, include all paths.
+pub fn count_commits(
+    dir: &Path,
+    since: &str,
+    include: &[String],
+    exclude: &[String],
+) -> Result<usize> {
+    let mut args = vec![
+        "rev-list".to_string(),
+        "--count".to_string(),
+        format!("{}..HEAD", since),
+        "--".to_string(),
+    ];
+    if include.is_empty() {
+        args.push(":(top)".to_string());
+    }
+    args.extend(include.iter().map(|glob| format!(":(top,glob){}", glob)));
+    args.extend(
+        exclude
+            .iter()
+            .map(|glob| format!(":(top,glob,exclude){}", glob)),
+    );
+    git(dir, args)?
+        .trim()
+        .parse()
+        .map_err(|_| Error::Git("unexpected output from `git rev-list`".to_string()))
+}
+
+/// Return the content of `path` at revision `rev`.
+pub fn show(dir: &Path, rev: &str, path: &Path) -> Result<String> {
+    let path = path.file_name().map(Path::new).unwrap_or(path);
+    let mut object = format!("{}:./", rev);
+    object.push_str(&path.to_string_lossy());
+    git(dir, ["show", object.as_str()])
+}
+
+/// Return a unified diff of `path` between `base` and the working tree.
+pub fn diff(dir: &Path, base: &str, path: &Path) -> Result<String> {
+    let path = path.file_name().map(Path::new).unwrap_or(path);
+    git(
+        dir,
+        [
+            OsStr::new("diff"),
+            OsStr::new("--unified=0"),
+            // Ignore user configuration that changes the output format.
+            OsStr::new("--no-color"),
+            OsStr::new("--no-ext-diff"),
+            OsStr::new("--src-prefix=a/"),
+            OsStr::new("--dst-prefix=b/"),
+            OsStr::new(base),
+            OsStr::new("--"),
+            path.as_os_str(),
+        ],
+    )
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    use tempfile::TempDir;
+
+    /// A throwaway git repository.
+    pub struct Repository {
+        pub dir: TempDir,
+    }
+
+    impl Repository {
+        pub fn new() -> Self {
+            let repository = Self {
+                dir: TempDir::new().unwrap(),
+            };
+            repository.git(["init", "--quiet", "--initial-branch=main"]);
+            repository
+        }
+
+        pub fn path(&self) -> &Path {
+            self.dir.path()
+        }
+
+        pub fn git<I, S>(&self, args: I) -> String
+        where
+            I: IntoIterator<Item = S>,
+            S: AsRef<OsStr>,
+        {
+            let output = Command::new("git")
+                .arg("-C")
+                .arg(self.path())
+                .args(["-c", "user.name=nb", "-c", "user.email=nb@example.org"])
+                .args(["-c", "commit.gpgsign=false", "-c", "tag.gpgsign=false"])
+                .args(args)
+                .output()
+                .unwrap();
+            assert!(
+                output.status.success(),
+                "{}",
+                String::from_utf8_lossy(&output.stderr)
+            );
+            String::from_utf8_lossy(&output.stdout).into_owned()
+        }
+
+        /// Write a file and commit it with the given message and date.
+        pub fn commit(&self, path: &str, content: &str, message: &str, date: &str) {
+            let file = self.path().join(path);
+            std::fs::create_dir_all(file.parent().unwrap()).unwrap();
+            std::fs::write(file, content).unwrap();
+            self.git(["add", path]);
+            let date = format!("{}T12:00:00+00:00", date);
+            let output = Command::new("git")
+                .arg("-C")
+                .arg(self.path())
+                .args(["-c", "user.name=nb", "-c", "user.email=nb@example.org"])
+                .args([
+                    "-c",
+                    "commit.gpgsign=false",
+                    "commit",
+                    "--quiet",
+                    "-m",
+                    message,
+                ])
+                .env("GIT_AUTHOR_DATE", &date)
+                .env("GIT_COMMITTER_DATE", &date)
+                .output()
+                .unwrap();
+            assert!(output.status.success());
+        }
+    }
+
+    #[test]
+    fn test_tags() {
+        let repository = Repository::new();
+        repository.commit("README.md", "foo", "Initial commit", "2025-01-01");
+        repository.git(["tag", "v0.1.0"]);
+        repository.commit("README.md", "bar", "Update README", "2025-02-01");
+        repository.git(["tag", "v0.2.0"]);
+        let mut tags = tags(repository.path()).unwrap();
+        tags.sort();
+        assert_eq!(
+            tags,
+            vec![
+                ("v0.1.0".to_string(), Date::new(2025, 1, 1)),
+                ("v0.2.0".to_string(), Date::new(2025, 2, 1)),
+            ]
+        );
+        assert!(matches!(
+            super::tags(&repository.path().join("missing")),
+            Err(Error::Io(_) | Error::Git(_))
+        ));
+    }
+
+    #[test]
+    fn test_commits() {
+        let repository = Repository::new();
+        repository.commit("README.md", "foo", "feat: add foo", "2025-01-01");
+        repository.git(["tag", "v0.1.0"]);
+        repository.git(["tag", "-a", "-m", "Release", "v0.1.1"]);
+        repository.commit(
+            "README.md",
+            "bar",
+            "fix: bar\n\nBody\n\nCloses #1",
+            "2025-01-02",
+        );
+        let commits = commits(repository.path(), "HEAD").unwrap();
+        assert_eq!(commits.len(), 2);
+        assert_eq!(commits[0].subject, "fix: bar");
+        assert_eq!(commits[0].body, "Body\n\nCloses #1");
+        assert!(commits[0].tags.is_empty());
+        assert_eq!(commits[1].subject, "feat: add foo");
+        assert_eq!(commits[1].tags, vec!["v0.1.1", "v0.1.0"]);
+        assert_eq!(
+            super::commits(repository.path(), "v0.1.0..HEAD")
+                .unwrap()
+                .len(),
+            1
+        );
+        assert!(super::commits(repository.path(), "missing").is_err());
+    }
+
+    #[test]
+    fn test_remote_url() {
+        let repository = Repository::new();
+        assert!(remote_url(repository.path()).is_err());
+        repository.git(["remote", "add", "origin", "git@github.com:owner/repo.git"]);
+        assert_eq!(
+            remote_url(repository.path()).unwrap(),
+            "https://github.com/owner/repo"
+        );
+    }
+
+    #[test]
+    fn test_web_url() {
+        for url in [
+            "https://github.com/owner/repo",
+            "https://github.com/owner/repo.git",
+            "https://github.com/owner/repo/",
+            "git@github.com:owner/repo.git",
+            "ssh://git@github.com/owner/repo.git",
+            "ssh://git@github.com:22/owner/repo",
+            "git://github.com/owner/repo.git",
+        ] {
+            assert_eq!(web_url(url), "https://github.com/owner/repo", "{}", url);
+        }
+    }
+
+    #[test]
+    fn test_latest_tag() {
+        let repository = Repository::new();
+        repository.commit("README.md", "foo", "Initial commit", "2025-01-01");
+        assert_eq!(latest_tag(repository.path(), "v{version}").unwrap(), None);
+        repository.git(["tag", "v0.1.0"]);
+        repository.commit("README.md", "bar", "Change README", "2025-01-02");
+        repository.git(["tag", "v0.2.0"]);
+        repository.git(["tag", "docs-published"]);
+        repository.commit("README.md", "baz", "Change README", "2025-01-03");
+        assert_eq!(
+            latest_tag(repository.path(), "v{version}").unwrap(),
+            Some("v0.2.0".to_string())
+        );
+        assert_eq!(
+            latest_tag(repository.path(), "release-{version}").unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_count_commits() {
+        let repository = Repository::new();
+        repository.commit("src/lib.rs", "foo", "Initial commit", "2025-01-01");
+        repository.git(["tag", "v0.1.0"]);
+        repository.commit("src/lib.rs", "bar", "Change library", "2025-01-02");
+        repository.commit("docs/index.md", "bar", "Change docs", "2025-01-03");
+        repository.commit("src/tests/foo.rs", "bar", "Add test", "2025-01-04");
+        assert_eq!(
+            latest_tag(repository.path(), "v{version}").unwrap(),
+            Some("v0.1.0".to_string())
+        );
+        let count = |include: &[&str], exclude: &[&str]| {
+            let include: Vec<String> = include.iter().map(|s| s.to_string()).collect();
+            let exclude: Vec<String> = exclude.iter().map(|s| s.to_string()).collect();
+            count_commits(repository.path(), "v0.1.0", &include, &exclude).unwrap()
+        };
+        assert_eq!(count(&[], &[]), 3);
+        assert_eq!(count(&["src/**"], &[]), 2);
+        assert_eq!(count(&["src/**"], &["src/tests/**"]), 1);
+        assert_eq!(count(&[], &["docs/**"]), 2);
+        assert_eq!(count(&["*.md"], &[]), 0);
+    }
+
+    #[test]
+    fn test_diff() {
+        let repository = Repository::new();
+        repository.commit("CHANGELOG.md", "foo\n", "Initial commit", "2025-01-01");
+        std::fs::write(repository.path().join("CHANGELOG.md"), "foo\nbar\n").unwrap();
+        let diff = diff(repository.path(), "HEAD", Path::new("CHANGELOG.md")).unwrap();
+        assert!(diff.contains("+++ b/CHANGELOG.md"));
+        assert!(diff.contains("@@ -1,0 +2 @@ foo\n+bar"));
+    }
+}
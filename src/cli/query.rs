@@ -0,0 +1,300 @@
+//! Query changelog data with simple selectors.
+//!
+//! A query is a path like `releases[0].version`, optionally followed by filters separated by `|`:
+//!
+//! * `where PATH` keeps the elements of an array for which `PATH` is set and not `false` or empty.
+//! * `where not PATH` keeps the other elements.
+//! * `length` returns the number of elements of an array or object, or the length of a string.
+//! * `first` and `last` return the first and last elements of an array.
+//!
+//! Selecting a field of an array selects it in each element, so `releases.version` lists all
+//! versions.
+use std::io::Write;
+
+use serde_json::{Map, Value, json};
+
+use crate::changelog::owned::{OwnedChangelog, OwnedChanges};
+
+use super::error::{Error, Result};
+use super::report::DataFormat;
+
+/// A step in a path.
+#[derive(Debug, PartialEq)]
+enum Step<'a> {
+    Field(&'a str),
+    /// An index into an array, from the end if negative.
+    Index(isize),
+}
+
+/// A filter after a path.
+#[derive(Debug, PartialEq)]
+enum Filter<'a> {
+    Where(Vec<Step<'a>>, bool),
+    Length,
+    First,
+    Last,
+}
+
+/// Return the changelog as a value to query.
+///
+/// The value has `title`, `unreleased`, `releases`, and `latest` (the first release). Changes are
+/// objects that map change types to lists of entries.
+pub fn value(changelog: &OwnedChangelog) -> Value {
+    let releases: Vec<Value> = changelog
+        .releases
+        .iter()
+        .map(|release| {
+            json!({
+                "version": release.version,
+                "url": release.url,
+                "date": release.date,
+                "yanked": release.yanked,
+                "changes": changes(&release.changes),
+            })
+        })
+        .collect();
+    json!({
+        "title": changelog.title,
+        "unreleased": changelog.unreleased.as_ref().map(|unreleased| json!({
+            "url": unreleased.url,
+            "changes": changes(&unreleased.changes),
+        })),
+        "latest": releases.first(),
+        "releases": releases,
+    })
+}
+
+fn changes(changes: &[OwnedChanges]) -> Value {
+    let mut map = Map::new();
+    for c in changes {
+        let items = map
+            .entry(c.kind.clone())
+            .or_insert_with(|| Value::Array(vec![]));
+        if let Value::Array(items) = items {
+            items.extend(c.items.iter().cloned().map(Value::String));
+        }
+    }
+    Value::Object(map)
+}
+
+/// Evaluate `query` against `value`.
+///
+/// Missing fields and indexes out of range select `null`.
+pub fn query(value: &Value, query: &str) -> Result<Value> {
+    let mut parts = query.split('|');
+    let path = parse_path(parts.next().unwrap_or_default().trim())?;
+    let filters = parts
+        .map(|part| parse_filter(part.trim()))
+        .collect::<Result<Vec<_>>>()?;
+    let mut value = select(value, &path);
+    for filter in &filters {
+        value = apply(value, filter);
+    }
+    Ok(value)
+}
+
+/// Write a query result in `format`.
+///
+/// In text format, strings are written without quotes, and arrays of strings, numbers, and
+/// booleans are written one element per line. Other values are written as JSON.
+pub fn print<W: Write>(w: &mut W, value: &Value, format: DataFormat) -> std::io::Result<()> {
+    match (format, value) {
+        (DataFormat::Text, Value::Null) => Ok(()),
+        (DataFormat::Text, Value::Array(values))
+            if values.iter().all(|v| !v.is_array() && !v.is_object()) =>
+        {
+            values.iter().try_for_each(|v| writeln!(w, "{}", text(v)))
+        }
+        (DataFormat::Text, Value::Object(_) | Value::Array(_)) | (DataFormat::Json, _) => {
+            serde_json::to_writer_pretty(&mut *w, value)?;
+            writeln!(w)
+        }
+        (DataFormat::Text, value) => writeln!(w, "{}", text(value)),
+    }
+}
+
+fn text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Null => String::new(),
+        value => value.to_string(),
+    }
+}
+
+fn parse_path(path: &str) -> Result<Vec<Step<'_>>> {
+    let invalid = || Error::Usage(format!("invalid query path `{}`", path));
+    let mut steps = Vec::new();
+    let mut rest = path;
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix('[') {
+            let (index, after) = after.split_once(']').ok_or_else(invalid)?;
+            steps.push(Step::Index(index.trim().parse().map_err(|_| invalid())?));
+            rest = after;
+        } else {
+            let after = match steps.is_empty() {
+                true => rest,
+                false => rest.strip_prefix('.').ok_or_else(invalid)?,
+            };
+            let end = after
+                .find(|c: char| !c.is_alphanumeric() && c != '_' && c != '-')
+                .unwrap_or(after.len());
+            if end == 0 {
+                return Err(invalid());
+            }
+            steps.push(Step::Field(&after[..end]));
+            rest = &after[end..];
+        }
+    }
+    Ok(steps)
+}
+
+fn parse_filter(filter: &str) -> Result<Filter<'_>> {
+    let mut words = filter.split_whitespace();
+    match (words.next(), words.next(), words.next(), words.next()) {
+        (Some("where"), Some("not"), Some(path), None) => {
+            Ok(Filter::Where(parse_path(path)?, false))
+        }
+        (Some("where"), Some(path), None, None) => Ok(Filter::Where(parse_path(path)?, true)),
+        (Some("length"), None, None, None) => Ok(Filter::Length),
+        (Some("first"), None, None, None) => Ok(Filter::First),
+        (Some("last"), None, None, None) => Ok(Filter::Last),
+        _ => Err(Error::Usage(format!(
+            "invalid query filter `{}` (expected `where PATH`, `where not PATH`, `length`, `first`, or `last`)",
+            filter
+        ))),
+    }
+}
+
+fn select(value: &Value, path: &[Step]) -> Value {
+    let Some((step, rest)) = path.split_first() else {
+        return value.clone();
+    };
+    match (step, value) {
+        (Step::Field(name), Value::Object(map)) => {
+            select(map.get(*name).unwrap_or(&Value::Null), rest)
+        }
+        (Step::Field(_), Value::Array(values)) => {
+            Value::Array(values.iter().map(|v| select(v, path)).collect())
+        }
+        (Step::Index(i), Value::Array(values)) => {
+            let i = match *i {
+                i if i < 0 => values.len().checked_sub(i.unsigned_abs()),
+                i => Some(i as usize),
+            };
+            match i.and_then(|i| values.get(i)) {
+                Some(value) => select(value, rest),
+                None => Value::Null,
+            }
+        }
+        _ => Value::Null,
+    }
+}
+
+fn apply(value: Value, filter: &Filter) -> Value {
+    match (filter, value) {
+        (Filter::Where(path, expected), Value::Array(values)) => Value::Array(
+            values
+                .into_iter()
+                .filter(|v| is_truthy(&select(v, path)) == *expected)
+                .collect(),
+        ),
+        (Filter::Length, Value::Array(values)) => values.len().into(),
+        (Filter::Length, Value::Object(map)) => map.len().into(),
+        (Filter::Length, Value::String(s)) => s.chars().count().into(),
+        (Filter::First, Value::Array(values)) => values.into_iter().next().unwrap_or_default(),
+        (Filter::Last, Value::Array(values)) => values.into_iter().last().unwrap_or_default(),
+        _ => Value::Null,
+    }
+}
+
+fn is_truthy(value: &Value) -> bool {
+    match value {
+        Value::Null | Value::Bool(false) => false,
+        Value::String(s) => !s.is_empty(),
+        Value::Array(values) => !values.is_empty(),
+        Value::Object(map) => !map.is_empty(),
+        _ => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHANGELOG: &str = "\
+# Changelog
+
+## [Unreleased]
+
+### Fixed
+
+* Fix foo
+* Fix bar
+
+## [1.1.0] - 2025-02-01 [YANKED]
+
+### Added
+
+* Add foo
+
+## [1.0.0] - 2025-01-01
+
+### Added
+
+* Add bar
+
+[unreleased]: https://example.org/compare/v1.1.0...HEAD
+[1.1.0]: https://example.org/compare/v1.0.0...v1.1.0
+[1.0.0]: https://example.org/releases/tag/v1.0.0
+";
+
+    fn run(q: &str) -> String {
+        let value = value(&crate::parse(CHANGELOG).to_owned());
+        let mut output = Vec::new();
+        print(&mut output, &query(&value, q).unwrap(), DataFormat::Text).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn test_query() {
+        assert_eq!(run("releases[0].version"), "1.1.0\n");
+        assert_eq!(run("releases[-1].version"), "1.0.0\n");
+        assert_eq!(run("latest.date"), "2025-02-01\n");
+        assert_eq!(run("unreleased.changes.Fixed"), "Fix foo\nFix bar\n");
+        assert_eq!(run("unreleased.changes | length"), "1\n");
+        assert_eq!(run("releases.version"), "1.1.0\n1.0.0\n");
+        assert_eq!(run("releases | where yanked | length"), "1\n");
+        assert_eq!(run("releases[5].version"), "");
+        assert_eq!(run("title.foo"), "");
+        insta::assert_snapshot!(run("releases | where not yanked"));
+    }
+
+    #[test]
+    fn test_query_json() {
+        let value = value(&crate::parse(CHANGELOG).to_owned());
+        let mut output = Vec::new();
+        let result = query(&value, "releases.yanked").unwrap();
+        print(&mut output, &result, DataFormat::Json).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "[\n  true,\n  false\n]\n"
+        );
+    }
+
+    #[test]
+    fn test_invalid_query() {
+        for q in [
+            "releases[x]",
+            "releases[0",
+            "releases..version",
+            ".title",
+            "releases | sort",
+        ] {
+            assert!(
+                matches!(query(&Value::Null, q), Err(Error::Usage(_))),
+                "{}",
+                q
+            );
+        }
+    }
+}
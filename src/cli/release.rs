@@ -0,0 +1,478 @@
+//! Move unreleased changes and fragments into a new release section.
+use std::path::PathBuf;
+
+use crate::changelog::owned::{OwnedChanges, OwnedRelease};
+use crate::changelog::parsed::ParsedChangelog;
+use crate::linter::CHANGE_TYPES;
+use crate::ruleset::RuleSet;
+use crate::span::Span;
+
+use super::error::{Error, Result};
+use super::fragments::{self, Fragment};
+use super::render;
+
+/// Merge the changes in fragments by change type, in the order of [`CHANGE_TYPES`].
+///
+/// `fragments` are paths and contents. Return an error if a fragment is invalid.
+pub fn changes(fragments: &[(PathBuf, String)]) -> Result<Vec<OwnedChanges>> {
+    let mut sections: Vec<OwnedChanges> = Vec::new();
+    for (path, content) in fragments {
+        let fragment = Fragment::parse(path, content);
+        if let Some(diagnostic) = fragments::lint(&fragment, RuleSet::default_static()).first() {
+            return Err(Error::Usage(diagnostic.message(content)).in_file(path));
+        }
+        // A fragment without diagnostics has a valid change type.
+        let kind = fragment.change_type().unwrap_or_default();
+        let items = fragment.items();
+        match sections.iter_mut().find(|s| s.kind == kind) {
+            Some(section) => section.items.extend(items),
+            None => sections.push(OwnedChanges {
+                kind: kind.to_string(),
+                items,
+            }),
+        }
+    }
+    sections.sort_by_key(|s| order(&s.kind));
+    Ok(sections)
+}
+
+/// Return the position of a change type in [`CHANGE_TYPES`], or after them if it is unknown.
+fn order(kind: &str) -> usize {
+    CHANGE_TYPES
+        .iter()
+        .position(|t| *t == kind)
+        .unwrap_or(CHANGE_TYPES.len())
+}
+
+/// Return the new unreleased URL and the URL of the release tagged `tag`, from the current
+/// unreleased URL.
+///
+/// The unreleased URL must compare the previous release with `HEAD` (`.../compare/v1.0.0...HEAD`)
+/// or list the commits of `HEAD` (`.../commits/HEAD`).
+pub fn links(unreleased_url: &str, tag: &str) -> Result<(String, String)> {
+    if let Some((base, range)) = unreleased_url.rsplit_once("/compare/")
+        && let Some(previous) = range.strip_suffix("...HEAD")
+    {
+        return Ok((
+            format!("{}/compare/{}...HEAD", base, tag),
+            format!("{}/compare/{}...{}", base, previous, tag),
+        ));
+    }
+    if let Some(base) = unreleased_url.strip_suffix("/commits/HEAD") {
+        return Ok((
+            format!("{}/compare/{}...HEAD", base, tag),
+            format!("{}/releases/tag/{}", base, tag),
+        ));
+    }
+    Err(Error::Usage(format!(
+        "cannot update the unreleased link `{}`; expected a URL ending in `/compare/TAG...HEAD` or `/commits/HEAD`",
+        unreleased_url
+    )))
+}
+
+/// Return `source` with the unreleased section moved into `release`, and the changes of
+/// `release` added to it.
+///
+/// The release follows the unreleased heading and keeps the content of the unreleased section as
+/// written, up to the end of `region`. Changes are added to the end of the section of the same
+/// type, or in a new section. Sections without content are dropped. If `unreleased_url` is set,
+/// the unreleased link definition is replaced with it and the release link definition follows it.
+pub fn insert(
+    source: &str,
+    changelog: &ParsedChangelog,
+    region: Span,
+    release: &OwnedRelease,
+    unreleased_url: Option<&str>,
+) -> Result<String> {
+    let no_unreleased = || Error::Usage("the changelog has no unreleased section".to_string());
+    let unreleased = changelog.unreleased.as_ref().ok_or_else(no_unreleased)?;
+    let start = line_end(source, unreleased.heading_span.end);
+    let definitions = link_definitions(source, start);
+    let end = changelog
+        .releases
+        .iter()
+        .map(|r| r.heading_span.start)
+        .find(|&i| i >= start)
+        .or_else(|| definitions.first().map(|(i, _)| *i))
+        .map_or(region.end, |i| i.min(region.end))
+        .max(start);
+    // Keep the whitespace after the last block of the unreleased section.
+    let end = start + source[start..end].trim_end().len();
+    let level = changelog.level + 2;
+    let sections = &unreleased.changes;
+    // The end of each section, before the next section or the end of the unreleased section.
+    let section_end = |i: usize| sections.get(i + 1).map_or(end, |s| s.heading_span.start);
+    let mut body_edits = Vec::new();
+    for (i, section) in sections.iter().enumerate() {
+        let content = &source[section.heading_span.end..section_end(i)];
+        if content.trim().is_empty()
+            && !release.changes.iter().any(|c| c.kind == section.kind.value)
+        {
+            body_edits.push((section.heading_span.start, section_end(i), String::new()));
+        }
+    }
+    for changes in &release.changes {
+        match sections.iter().rposition(|s| s.kind.value == changes.kind) {
+            Some(i) => {
+                let section = &sections[i];
+                let content = source[section.heading_span.start..section_end(i)].trim_end();
+                let position = section.heading_span.start + content.len();
+                // Continue the last list, or start one after the heading or other content.
+                let last_line = content.rsplit('\n').next().unwrap_or_default();
+                let marker = list_marker(content);
+                let separator = match marker {
+                    Some(_) if last_line.starts_with(' ') || list_marker(last_line).is_some() => {
+                        "\n"
+                    }
+                    _ => "\n\n",
+                };
+                let mut marker = marker.unwrap_or(ListMarker::Bullet('*'));
+                let items: Vec<String> = changes
+                    .items
+                    .iter()
+                    .map(|item| format!("{} {}", marker.next(), item))
+                    .collect();
+                body_edits.push((
+                    position,
+                    position,
+                    separator.to_string() + &items.join("\n"),
+                ));
+            }
+            None => {
+                let text = render::changes(changes, level);
+                match sections
+                    .iter()
+                    .find(|s| order(s.kind.value) > order(&changes.kind))
+                {
+                    Some(next) => {
+                        let position = next.heading_span.start;
+                        body_edits.push((position, position, text + "\n"));
+                    }
+                    None => {
+                        body_edits.push((end, end, format!("\n\n{}", text.trim_end())));
+                    }
+                }
+            }
+        }
+    }
+    // Apply the edits from the end, so that deletions come before insertions at the same offset.
+    body_edits.sort_by_key(|(start, end, _)| (*start, *end));
+    let mut body = source[start..end].to_string();
+    for (edit_start, edit_end, text) in body_edits.into_iter().rev() {
+        body.replace_range(edit_start - start..edit_end - start, &text);
+    }
+    let heading = OwnedRelease {
+        version: release.version.clone(),
+        url: release.url.clone(),
+        date: release.date.clone(),
+        yanked: release.yanked,
+        changes: vec![],
+    };
+    let mut text = format!("\n{}", render::release(&heading, level - 1));
+    let body = body.trim_matches('\n');
+    if !body.trim().is_empty() {
+        text.push('\n');
+        text.push_str(body);
+    } else {
+        text.pop();
+    }
+    // Separate the release from the content that follows it with a blank line.
+    let following = &source[end..];
+    if !following.trim().is_empty() {
+        let whitespace = &following[..following.len() - following.trim_start().len()];
+        let newlines = whitespace.matches('\n').count();
+        text.push_str(&"\n".repeat(2usize.saturating_sub(newlines)));
+    }
+    let mut edits = vec![(start, end, text)];
+    if let Some(url) = unreleased_url {
+        let (def_start, label) = definitions
+            .iter()
+            .find(|(_, label)| label.eq_ignore_ascii_case("unreleased"))
+            .ok_or_else(|| {
+                Error::Usage("cannot find the unreleased link definition".to_string())
+            })?;
+        let def_end = source[*def_start..]
+            .find('\n')
+            .map_or(source.len(), |i| def_start + i);
+        let mut text = format!("[{}]: {}", label, url);
+        if let Some(release_url) = &release.url {
+            text.push_str(&format!("\n[{}]: {}", release.version, release_url));
+        }
+        edits.push((*def_start, def_end, text));
+    }
+    let mut result = source.to_string();
+    for (start, end, text) in edits.into_iter().rev() {
+        result.replace_range(start..end, &text);
+    }
+    if !result.ends_with('\n') {
+        result.push('\n');
+    }
+    Ok(result)
+}
+
+/// The marker of a list item.
+enum ListMarker {
+    Bullet(char),
+    Ordered(usize, char),
+}
+
+impl ListMarker {
+    /// Return the marker for the next item.
+    fn next(&mut self) -> String {
+        match self {
+            ListMarker::Bullet(c) => c.to_string(),
+            ListMarker::Ordered(n, delimiter) => {
+                *n += 1;
+                format!("{}{}", n, delimiter)
+            }
+        }
+    }
+}
+
+/// Return the marker of the last item of a top-level list in `content`.
+fn list_marker(content: &str) -> Option<ListMarker> {
+    content.lines().rev().find_map(|line| {
+        let digits = line.find(|c: char| !c.is_ascii_digit())?;
+        let mut chars = line[digits..].chars();
+        let marker = chars.next()?;
+        if !matches!(chars.next(), Some(' ' | '\t')) {
+            return None;
+        }
+        match (digits, marker) {
+            (0, '*' | '-' | '+') => Some(ListMarker::Bullet(marker)),
+            (1..=9, '.' | ')') => Some(ListMarker::Ordered(line[..digits].parse().ok()?, marker)),
+            _ => None,
+        }
+    })
+}
+
+/// Return the offset of the end of the line that contains `offset`, after the newline.
+fn line_end(source: &str, offset: usize) -> usize {
+    source[offset..]
+        .find('\n')
+        .map_or(source.len(), |i| offset + i + 1)
+}
+
+/// Return the offsets and labels of link definitions at the start of a line after `start`.
+fn link_definitions(source: &str, start: usize) -> Vec<(usize, &str)> {
+    let mut definitions = Vec::new();
+    let mut offset = start;
+    for line in source[start..].split_inclusive('\n') {
+        if let Some(rest) = line.strip_prefix('[')
+            && let Some((label, _)) = rest.split_once("]:")
+        {
+            definitions.push((offset, label));
+        }
+        offset += line.len();
+    }
+    definitions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::parse;
+
+    fn changes(kind: &str, items: &[&str]) -> OwnedChanges {
+        OwnedChanges {
+            kind: kind.to_string(),
+            items: items.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    fn kinds(changes: &[OwnedChanges]) -> Vec<(&str, Vec<&str>)> {
+        changes
+            .iter()
+            .map(|c| {
+                (
+                    c.kind.as_str(),
+                    c.items.iter().map(String::as_str).collect(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_changes() {
+        let fragments = [
+            (PathBuf::from("1.fixed.md"), "Fix bar\n".to_string()),
+            (
+                PathBuf::from("2.md"),
+                "---\ntype: added\n---\n\n* Add baz\n* Add qux\n".to_string(),
+            ),
+            (PathBuf::from("3.security.md"), "Fix quux\n".to_string()),
+        ];
+        let merged = super::changes(&fragments).unwrap();
+        assert_eq!(
+            kinds(&merged),
+            vec![
+                ("Added", vec!["Add baz", "Add qux"]),
+                ("Fixed", vec!["Fix bar"]),
+                ("Security", vec!["Fix quux"]),
+            ]
+        );
+        let fragments = [(PathBuf::from("1.features.md"), "Foo\n".to_string())];
+        let Err(error) = super::changes(&fragments) else {
+            panic!("expected an error");
+        };
+        assert_eq!(
+            error.to_string(),
+            "1.features.md: Invalid change type `features`"
+        );
+    }
+
+    #[test]
+    fn test_links() {
+        assert_eq!(
+            links("https://example.org/compare/v1.0.0...HEAD", "v1.1.0").unwrap(),
+            (
+                "https://example.org/compare/v1.1.0...HEAD".to_string(),
+                "https://example.org/compare/v1.0.0...v1.1.0".to_string()
+            )
+        );
+        assert_eq!(
+            links("https://example.org/commits/HEAD", "v1.0.0").unwrap(),
+            (
+                "https://example.org/compare/v1.0.0...HEAD".to_string(),
+                "https://example.org/releases/tag/v1.0.0".to_string()
+            )
+        );
+        assert!(matches!(
+            links("https://example.org/", "v1.0.0"),
+            Err(Error::Usage(_))
+        ));
+    }
+
+    #[test]
+    fn test_insert() {
+        let source = "\
+# Changelog
+
+## [Unreleased]
+
+### Fixed
+
+* Fix foo
+
+## [1.0.0] - 2025-01-01
+
+### Added
+
+* Add foo
+
+[unreleased]: https://example.org/compare/v1.0.0...HEAD
+[1.0.0]: https://example.org/releases/tag/v1.0.0
+";
+        let release = OwnedRelease {
+            version: "1.1.0".to_string(),
+            url: Some("https://example.org/compare/v1.0.0...v1.1.0".to_string()),
+            date: Some("2025-02-01".to_string()),
+            yanked: false,
+            changes: vec![changes("Fixed", &["Fix bar"])],
+        };
+        let result = insert(
+            source,
+            &parse(source),
+            Span::new(0, source.len()),
+            &release,
+            Some("https://example.org/compare/v1.1.0...HEAD"),
+        )
+        .unwrap();
+        insta::assert_snapshot!(result);
+
+        let source =
+            "# Changelog\n\n## [Unreleased]\n\n[unreleased]: https://example.org/commits/HEAD\n";
+        let release = OwnedRelease {
+            url: Some("https://example.org/releases/tag/v1.1.0".to_string()),
+            ..release
+        };
+        let result = insert(
+            source,
+            &parse(source),
+            Span::new(0, source.len()),
+            &release,
+            Some("https://example.org/compare/v1.1.0...HEAD"),
+        )
+        .unwrap();
+        insta::assert_snapshot!(result);
+        assert!(matches!(
+            insert(
+                "# Changelog\n",
+                &parse("# Changelog\n"),
+                Span::new(0, 12),
+                &release,
+                None
+            ),
+            Err(Error::Usage(_))
+        ));
+    }
+
+    #[test]
+    fn test_insert_keeps_content() {
+        let source = "\
+# Changelog
+
+## [Unreleased]
+
+Some prose about the release.
+
+### Added
+
+- Add foo
+  - with a nested item
+- Add bar
+
+### Changed
+
+### Fixed
+
+1. Fix baz
+
+## [1.0.0] - 2025-01-01
+
+### Added
+
+* Add foo
+
+[unreleased]: https://example.org/compare/v1.0.0...HEAD
+[1.0.0]: https://example.org/releases/tag/v1.0.0
+";
+        let release = OwnedRelease {
+            version: "1.1.0".to_string(),
+            url: None,
+            date: Some("2025-02-01".to_string()),
+            yanked: false,
+            changes: vec![
+                changes("Added", &["Add qux"]),
+                changes("Deprecated", &["Deprecate foo"]),
+                changes("Fixed", &["Fix quux"]),
+                changes("Security", &["Fix corge"]),
+            ],
+        };
+        let result = insert(
+            source,
+            &parse(source),
+            Span::new(0, source.len()),
+            &release,
+            None,
+        )
+        .unwrap();
+        insta::assert_snapshot!(result);
+        let release = OwnedRelease {
+            changes: vec![],
+            ..release
+        };
+        let result = insert(
+            source,
+            &parse(source),
+            Span::new(0, source.len()),
+            &release,
+            None,
+        )
+        .unwrap();
+        assert!(result.contains("  - with a nested item\n- Add bar\n"));
+        assert!(result.contains("### Fixed\n\n1. Fix baz\n"));
+        assert!(result.contains("Some prose about the release."));
+    }
+}
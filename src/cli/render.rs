@@ -0,0 +1,129 @@
+//! Render changelogs in Keep a Changelog format.
+use std::fmt::Write;
+
+use crate::changelog::owned::{OwnedChangelog, OwnedChanges, OwnedRelease};
+
+/// The preamble of the Keep a Changelog template.
+pub const PREAMBLE: &str = "All notable changes to this project will be documented in this file.
+
+The format is based on [Keep a Changelog](https://keepachangelog.com/en/1.1.0/),
+and this project adheres to [Semantic Versioning](https://semver.org/spec/v2.0.0.html).";
+
+/// Render a changelog, with `preamble` after the title.
+///
+/// Headings with a URL are rendered as links, and the link definitions follow the last release.
+pub fn changelog(changelog: &OwnedChangelog, preamble: Option<&str>) -> String {
+    let mut blocks = Vec::new();
+    if let Some(title) = &changelog.title {
+        blocks.push(format!("# {}\n", title));
+    }
+    if let Some(preamble) = preamble {
+        blocks.push(format!("{}\n", preamble.trim_end()));
+    }
+    let mut links = String::new();
+    if let Some(unreleased) = &changelog.unreleased {
+        let mut s = heading(2, "Unreleased", unreleased.url.as_deref(), None);
+        for changes in &unreleased.changes {
+            s.push('\n');
+            s.push_str(&self::changes(changes, 3));
+        }
+        blocks.push(s);
+        if let Some(url) = &unreleased.url {
+            writeln!(links, "[unreleased]: {}", url).unwrap();
+        }
+    }
+    for release in &changelog.releases {
+        blocks.push(self::release(release, 2));
+        if let Some(url) = &release.url {
+            writeln!(links, "[{}]: {}", release.version, url).unwrap();
+        }
+    }
+    if !links.is_empty() {
+        blocks.push(links);
+    }
+    blocks.join("\n")
+}
+
+/// Render a release section with a heading of `level`, without its link definition.
+pub fn release(release: &OwnedRelease, level: usize) -> String {
+    let mut s = heading(
+        level,
+        &release.version,
+        release.url.as_deref(),
+        release.date.as_deref(),
+    );
+    if release.yanked {
+        s.insert_str(s.len() - 1, " [YANKED]");
+    }
+    for changes in &release.changes {
+        s.push('\n');
+        s.push_str(&self::changes(changes, level + 1));
+    }
+    s
+}
+
+/// Render a change type section with a heading of `level`.
+pub fn changes(changes: &OwnedChanges, level: usize) -> String {
+    let mut s = format!("{} {}\n\n", "#".repeat(level), changes.kind);
+    for item in &changes.items {
+        writeln!(s, "* {}", item).unwrap();
+    }
+    s
+}
+
+fn heading(level: usize, name: &str, url: Option<&str>, date: Option<&str>) -> String {
+    let hashes = "#".repeat(level);
+    let mut s = match url {
+        Some(_) => format!("{} [{}]", hashes, name),
+        None => format!("{} {}", hashes, name),
+    };
+    if let Some(date) = date {
+        write!(s, " - {}", date).unwrap();
+    }
+    s.push('\n');
+    s
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::changelog::owned::OwnedUnreleased;
+
+    #[test]
+    fn test_changelog() {
+        let changelog = OwnedChangelog {
+            title: Some("Changelog".to_string()),
+            unreleased: Some(OwnedUnreleased {
+                url: Some("https://example.org/unreleased".to_string()),
+                changes: vec![],
+            }),
+            releases: vec![
+                OwnedRelease {
+                    version: "1.0.0".to_string(),
+                    url: Some("https://example.org/1.0.0".to_string()),
+                    date: Some("2025-02-01".to_string()),
+                    yanked: true,
+                    changes: vec![
+                        OwnedChanges {
+                            kind: "Added".to_string(),
+                            items: vec!["Add foo".to_string(), "Add bar".to_string()],
+                        },
+                        OwnedChanges {
+                            kind: "Fixed".to_string(),
+                            items: vec!["Fix baz".to_string()],
+                        },
+                    ],
+                },
+                OwnedRelease {
+                    version: "0.1.0".to_string(),
+                    url: None,
+                    date: Some("2025-01-01".to_string()),
+                    yanked: false,
+                    changes: vec![],
+                },
+            ],
+        };
+        insta::assert_snapshot!(super::changelog(&changelog, Some(PREAMBLE)));
+    }
+}
@@ -2,34 +2,71 @@ use std::io::Write;
 use std::path::Path;
 
 use crate::diagnostic::Diagnostic;
-use crate::span::Locator;
-use crate::span::Position;
+use crate::span::{Encoding, Locator, Position};
 
 pub(crate) mod context;
 pub(crate) mod format;
 pub(crate) mod formatter;
 
 pub(crate) use context::Context;
-pub(crate) use format::Format;
+pub(crate) use format::{DataFormat, Format};
 pub(crate) use formatter::Formatter;
 
+/// The diagnostics for a file.
+pub struct File<'a> {
+    pub source: &'a str,
+    pub path: Option<&'a Path>,
+    pub diagnostics: &'a [Diagnostic<Position>],
+}
+
+/// Report the diagnostics for all files, with columns in `encoding`.
 pub fn report<W: Write>(
     w: &mut W,
-    diagnostics: &[Diagnostic<Position>],
-    source: &str,
-    path: Option<&Path>,
-    locator: &Locator,
+    files: &[File],
     format: Format,
+    encoding: Encoding,
 ) -> std::io::Result<()> {
-    let context = Context {
-        source,
-        path,
-        locator,
-    };
+    let locators: Vec<Locator> = files
+        .iter()
+        .map(|f| Locator::new(f.source).with_encoding(encoding))
+        .collect();
+    let files: Vec<(&[Diagnostic<Position>], Context)> = files
+        .iter()
+        .zip(&locators)
+        .map(|(file, locator)| {
+            let context = Context {
+                source: file.source,
+                path: file.path,
+                locator,
+            };
+            (file.diagnostics, context)
+        })
+        .collect();
     match format {
-        Format::Short => formatter::ShortFormatter.format(w, diagnostics, &context),
-        Format::Full => formatter::FullFormatter.format(w, diagnostics, &context),
-        Format::Json => formatter::JsonFormatter.format(w, diagnostics, &context),
-        Format::JsonLines => formatter::JsonLinesFormatter.format(w, diagnostics, &context),
+        Format::Short => formatter::ShortFormatter.format_all(w, &files),
+        Format::Full => formatter::FullFormatter.format_all(w, &files),
+        Format::Json => formatter::JsonFormatter.format_all(w, &files),
+        Format::JsonLines => formatter::JsonLinesFormatter.format_all(w, &files),
+    }
+}
+
+/// Write a summary of the diagnostics for all files.
+pub fn summary<W: Write>(w: &mut W, files: &[File]) -> std::io::Result<()> {
+    let count = files.iter().map(|f| f.diagnostics.len()).sum();
+    let failed = files.iter().filter(|f| !f.diagnostics.is_empty()).count();
+    writeln!(
+        w,
+        "Found {} in {} of {}.",
+        plural(count, "diagnostic"),
+        failed,
+        plural(files.len(), "file")
+    )
+}
+
+fn plural(n: usize, noun: &str) -> String {
+    if n == 1 {
+        format!("{} {}", n, noun)
+    } else {
+        format!("{} {}s", n, noun)
     }
 }
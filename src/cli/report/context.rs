@@ -2,6 +2,7 @@ use std::path::Path;
 
 use crate::span::Locator;
 
+#[derive(Clone, Copy)]
 pub struct Context<'a> {
     pub source: &'a str,
     pub path: Option<&'a Path>,
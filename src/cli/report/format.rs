@@ -44,3 +44,11 @@ impl<'de> Deserialize<'de> for Format {
         deserializer.deserialize_str(FormatVisitor)
     }
 }
+
+/// The output format of commands that print changelog data instead of diagnostics.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub enum DataFormat {
+    #[default]
+    Text,
+    Json,
+}
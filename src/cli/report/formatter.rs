@@ -2,10 +2,9 @@ use std::fmt::Write as FmtWrite;
 use std::io::Write;
 
 use owo_colors::{OwoColorize, Stream};
-use serde::Serialize;
 
-use crate::diagnostic::Diagnostic;
-use crate::span::Position;
+use crate::diagnostic::{Diagnostic, JsonDiagnostic};
+use crate::span::{Encoding, Position};
 
 use super::Context;
 
@@ -16,6 +15,18 @@ pub trait Formatter {
         diagnostics: &[Diagnostic<Position>],
         context: &Context,
     ) -> std::io::Result<()>;
+
+    /// Format the diagnostics for several files.
+    fn format_all(
+        &self,
+        w: &mut dyn Write,
+        files: &[(&[Diagnostic<Position>], Context)],
+    ) -> std::io::Result<()> {
+        for (diagnostics, context) in files {
+            self.format(w, diagnostics, context)?;
+        }
+        Ok(())
+    }
 }
 
 pub struct ShortFormatter;
@@ -23,14 +34,6 @@ pub struct FullFormatter;
 pub struct JsonFormatter;
 pub struct JsonLinesFormatter;
 
-#[derive(Clone, Debug, Serialize)]
-pub struct JsonDiagnostic {
-    pub code: String,
-    pub position: Option<Position>,
-    pub path: Option<String>,
-    pub message: String,
-}
-
 impl Formatter for ShortFormatter {
     fn format(
         &self,
@@ -101,7 +104,10 @@ impl Formatter for FullFormatter {
                 .unwrap();
                 //     | ^E000
                 write!(&mut buf, "{}", gutter).unwrap();
-                buf.push_str(&" ".repeat(p.start.column));
+                // Align the caret by characters, whatever the column encoding.
+                let line_start = context.locator.line_span(p.start.line).unwrap().start;
+                let column = Encoding::Utf32.len(&context.source[line_start..p.start.offset]) + 1;
+                buf.push_str(&" ".repeat(column));
                 writeln!(
                     &mut buf,
                     "{} {}",
@@ -128,6 +134,17 @@ impl Formatter for FullFormatter {
                     .unwrap();
                 }
                 writeln!(&mut buf, "{}", gutter).unwrap();
+                //     = help:
+                if let Some(fix) = diagnostic.fixes.first() {
+                    writeln!(
+                        &mut buf,
+                        "{:>width$} {}",
+                        "=".dimmed(),
+                        format!("help: {}", fix.message).bold(),
+                        width = gutter_width + 1
+                    )
+                    .unwrap();
+                }
                 writeln!(w, "{}", std::mem::take(&mut buf))?;
             }
         }
@@ -142,13 +159,21 @@ impl Formatter for JsonFormatter {
         diagnostics: &[Diagnostic<Position>],
         context: &Context,
     ) -> std::io::Result<()> {
-        let json_diagnostics: Vec<JsonDiagnostic> = diagnostics
+        self.format_all(w, &[(diagnostics, *context)])
+    }
+
+    // Write a single array for all files.
+    fn format_all(
+        &self,
+        w: &mut dyn Write,
+        files: &[(&[Diagnostic<Position>], Context)],
+    ) -> std::io::Result<()> {
+        let json_diagnostics: Vec<JsonDiagnostic> = files
             .iter()
-            .map(|diagnostic| JsonDiagnostic {
-                code: diagnostic.code().to_string(),
-                position: diagnostic.position(context.locator),
-                path: context.path.map(|p| p.to_string_lossy().to_string()),
-                message: diagnostic.message(context.source),
+            .flat_map(|(diagnostics, context)| {
+                diagnostics.iter().map(|diagnostic| {
+                    JsonDiagnostic::new(diagnostic, context.source, context.locator, context.path)
+                })
             })
             .collect();
         Ok(serde_json::to_writer(&mut *w, &json_diagnostics)?)
@@ -162,16 +187,9 @@ impl Formatter for JsonLinesFormatter {
         diagnostics: &[Diagnostic<Position>],
         context: &Context,
     ) -> std::io::Result<()> {
-        let json_diagnostics: Vec<JsonDiagnostic> = diagnostics
-            .iter()
-            .map(|diagnostic| JsonDiagnostic {
-                code: diagnostic.code().to_string(),
-                position: diagnostic.position(context.locator),
-                path: context.path.map(|p| p.to_string_lossy().to_string()),
-                message: diagnostic.message(context.source),
-            })
-            .collect();
-        for diagnostic in json_diagnostics {
+        for diagnostic in diagnostics.iter().map(|diagnostic| {
+            JsonDiagnostic::new(diagnostic, context.source, context.locator, context.path)
+        }) {
             serde_json::to_writer(&mut *w, &diagnostic)?;
             writeln!(w)?;
         }
@@ -0,0 +1,691 @@
+//! Language server.
+//!
+//! The server speaks the Language Server Protocol over standard input and output. It publishes
+//! diagnostics when a changelog is opened or changed, and provides code actions for fixes, hovers
+//! that explain rules, document symbols for sections, and completion of change type headings.
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use lsp_server::{Connection, Message, Notification, Request, RequestId, Response};
+use lsp_types as lsp;
+use lsp_types::notification::Notification as _;
+use lsp_types::request::Request as _;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+use crate::changelog::parsed::{ParsedChangelog, ParsedChanges};
+use crate::diagnostic::Diagnostic;
+use crate::linter::{CHANGE_TYPES, Linter};
+use crate::ruleset::RuleSet;
+use crate::span::{Encoding, Locator, Span};
+
+use super::commands::{parse_changelog, repository_dir};
+use super::config::Config;
+use super::error::{Error, Result};
+
+/// Run the language server over standard input and output.
+pub fn server() -> Result<()> {
+    let (connection, io_threads) = Connection::stdio();
+    run(&connection)?;
+    drop(connection);
+    io_threads.join()?;
+    Ok(())
+}
+
+fn run(connection: &Connection) -> Result<()> {
+    let (id, params) = connection.initialize_start().map_err(server_error)?;
+    let params: lsp::InitializeParams = serde_json::from_value(params)?;
+    let encoding = position_encoding(&params.capabilities);
+    let capabilities = lsp::ServerCapabilities {
+        position_encoding: Some(match encoding {
+            Encoding::Utf8 => lsp::PositionEncodingKind::UTF8,
+            Encoding::Utf16 => lsp::PositionEncodingKind::UTF16,
+            Encoding::Utf32 => lsp::PositionEncodingKind::UTF32,
+        }),
+        text_document_sync: Some(lsp::TextDocumentSyncCapability::Kind(
+            lsp::TextDocumentSyncKind::FULL,
+        )),
+        code_action_provider: Some(lsp::CodeActionProviderCapability::Simple(true)),
+        hover_provider: Some(lsp::HoverProviderCapability::Simple(true)),
+        document_symbol_provider: Some(lsp::OneOf::Left(true)),
+        completion_provider: Some(lsp::CompletionOptions::default()),
+        ..Default::default()
+    };
+    let result = lsp::InitializeResult {
+        capabilities,
+        server_info: None,
+    };
+    connection
+        .initialize_finish(id, serde_json::to_value(result)?)
+        .map_err(server_error)?;
+    let mut documents: HashMap<lsp::Url, Document> = HashMap::new();
+    for message in &connection.receiver {
+        match message {
+            Message::Request(request) => {
+                if connection.handle_shutdown(&request).map_err(server_error)? {
+                    return Ok(());
+                }
+                let response = handle_request(&documents, encoding, request);
+                connection
+                    .sender
+                    .send(response.into())
+                    .map_err(server_error)?;
+            }
+            Message::Notification(notification) => {
+                let mut messages = Vec::new();
+                if let Some(uri) = handle_notification(&mut documents, notification, &mut messages)
+                {
+                    let diagnostics = match documents.get(&uri).map(|d| Analysis::new(d, encoding))
+                    {
+                        Some(Ok(analysis)) => analysis.diagnostics(),
+                        Some(Err(e)) => {
+                            messages.push((lsp::MessageType::ERROR, e.to_string()));
+                            vec![]
+                        }
+                        None => vec![],
+                    };
+                    let params = lsp::PublishDiagnosticsParams::new(uri, diagnostics, None);
+                    notify::<lsp::notification::PublishDiagnostics>(connection, params)?;
+                }
+                for (typ, message) in messages {
+                    let params = lsp::ShowMessageParams { typ, message };
+                    notify::<lsp::notification::ShowMessage>(connection, params)?;
+                }
+            }
+            Message::Response(_) => {}
+        }
+    }
+    Ok(())
+}
+
+/// Send a notification to the client.
+fn notify<N: lsp::notification::Notification>(
+    connection: &Connection,
+    params: N::Params,
+) -> Result<()> {
+    let notification = Notification::new(N::METHOD.to_string(), params);
+    connection
+        .sender
+        .send(notification.into())
+        .map_err(server_error)
+}
+
+/// Update the open documents. Return the URI of the document to publish diagnostics for.
+///
+/// Add messages to show to the user, like configuration errors, to `messages`.
+fn handle_notification(
+    documents: &mut HashMap<lsp::Url, Document>,
+    notification: Notification,
+    messages: &mut Vec<(lsp::MessageType, String)>,
+) -> Option<lsp::Url> {
+    match notification.method.as_str() {
+        lsp::notification::DidOpenTextDocument::METHOD => {
+            let params: lsp::DidOpenTextDocumentParams = params(notification.params)?;
+            let uri = params.text_document.uri;
+            let (document, error) = Document::open(&uri, params.text_document.text);
+            if let Some(e) = error {
+                messages.push((
+                    lsp::MessageType::WARNING,
+                    format!("{}; using the default configuration", e),
+                ));
+            }
+            documents.insert(uri.clone(), document);
+            Some(uri)
+        }
+        lsp::notification::DidChangeTextDocument::METHOD => {
+            let params: lsp::DidChangeTextDocumentParams = params(notification.params)?;
+            let uri = params.text_document.uri;
+            // The server only supports full document synchronization.
+            let text = params.content_changes.into_iter().last()?.text;
+            // Keep the configuration loaded when the document was opened.
+            documents.get_mut(&uri)?.text = text;
+            Some(uri)
+        }
+        lsp::notification::DidCloseTextDocument::METHOD => {
+            let params: lsp::DidCloseTextDocumentParams = params(notification.params)?;
+            let uri = params.text_document.uri;
+            documents.remove(&uri);
+            // Clear the diagnostics for the closed document.
+            Some(uri)
+        }
+        _ => None,
+    }
+}
+
+fn handle_request(
+    documents: &HashMap<lsp::Url, Document>,
+    encoding: Encoding,
+    request: Request,
+) -> Response {
+    let id = request.id.clone();
+    // Errors are shown when diagnostics are published, so requests on such documents return null.
+    let analysis = |uri: &lsp::Url| Analysis::new(documents.get(uri)?, encoding).ok();
+    match request.method.as_str() {
+        lsp::request::CodeActionRequest::METHOD => {
+            respond(id, request.params, |p: lsp::CodeActionParams| {
+                let uri = p.text_document.uri;
+                Some(analysis(&uri)?.code_actions(&uri, p.range))
+            })
+        }
+        lsp::request::HoverRequest::METHOD => respond(id, request.params, |p: lsp::HoverParams| {
+            let position = p.text_document_position_params;
+            analysis(&position.text_document.uri)?.hover(position.position)
+        }),
+        lsp::request::DocumentSymbolRequest::METHOD => {
+            respond(id, request.params, |p: lsp::DocumentSymbolParams| {
+                Some(lsp::DocumentSymbolResponse::Nested(
+                    analysis(&p.text_document.uri)?.symbols(),
+                ))
+            })
+        }
+        lsp::request::Completion::METHOD => {
+            respond(id, request.params, |p: lsp::CompletionParams| {
+                let position = p.text_document_position;
+                Some(lsp::CompletionResponse::Array(
+                    analysis(&position.text_document.uri)?.completions(position.position),
+                ))
+            })
+        }
+        _ => Response::new_err(
+            id,
+            lsp_server::ErrorCode::MethodNotFound as i32,
+            format!("unsupported method: {}", request.method),
+        ),
+    }
+}
+
+/// Respond to a request with the result of `f`, or `null`.
+fn respond<P, R, F>(id: RequestId, value: serde_json::Value, f: F) -> Response
+where
+    P: DeserializeOwned,
+    R: Serialize,
+    F: FnOnce(P) -> Option<R>,
+{
+    match serde_json::from_value::<P>(value) {
+        Ok(p) => Response::new_ok(id, f(p)),
+        Err(e) => Response::new_err(
+            id,
+            lsp_server::ErrorCode::InvalidParams as i32,
+            e.to_string(),
+        ),
+    }
+}
+
+/// Return the first position encoding the client supports, or UTF-16, which all clients support.
+fn position_encoding(capabilities: &lsp::ClientCapabilities) -> Encoding {
+    let encodings = capabilities
+        .general
+        .as_ref()
+        .and_then(|general| general.position_encodings.as_deref())
+        .unwrap_or_default();
+    encodings
+        .iter()
+        .find_map(|kind| match kind.as_str() {
+            "utf-8" => Some(Encoding::Utf8),
+            "utf-16" => Some(Encoding::Utf16),
+            "utf-32" => Some(Encoding::Utf32),
+            _ => None,
+        })
+        .unwrap_or(Encoding::Utf16)
+}
+
+fn params<P: DeserializeOwned>(value: serde_json::Value) -> Option<P> {
+    serde_json::from_value(value).ok()
+}
+
+fn server_error<E: std::fmt::Display>(e: E) -> Error {
+    Error::Server(e.to_string())
+}
+
+/// An open document and its configuration.
+struct Document {
+    text: String,
+    path: PathBuf,
+    config: Config,
+    ruleset: RuleSet,
+}
+
+impl Document {
+    /// Open a document and load its configuration.
+    ///
+    /// If the configuration is invalid, use the default configuration so that diagnostics remain
+    /// available while the user fixes it, and return the error.
+    fn open(uri: &lsp::Url, text: String) -> (Self, Option<Error>) {
+        let path = uri
+            .to_file_path()
+            .unwrap_or_else(|_| PathBuf::from(uri.path()));
+        let loaded = Config::discover(repository_dir(&path))
+            .and_then(|config| Ok((config.lint.rules(&path)?, config)));
+        let ((rules, config), error) = match loaded {
+            Ok(loaded) => (loaded, None),
+            Err(e) => {
+                let config = Config::default();
+                (
+                    (config.lint.rules(&path).unwrap_or_default(), config),
+                    Some(e),
+                )
+            }
+        };
+        let document = Self {
+            text,
+            path,
+            config,
+            ruleset: RuleSet::new(rules),
+        };
+        (document, error)
+    }
+}
+
+/// A linted document.
+struct Analysis<'a> {
+    source: &'a str,
+    changelog: ParsedChangelog<'a>,
+    diagnostics: Vec<Diagnostic>,
+    locator: Locator<'a>,
+}
+
+impl<'a> Analysis<'a> {
+    fn new(document: &'a Document, encoding: Encoding) -> Result<Self> {
+        let source = document.text.as_str();
+        let changelog = parse_changelog(&document.path, source, None, &document.config)?;
+        let diagnostics = Linter::new(&document.ruleset)
+            .with_today(document.config.lint.today)
+            .lint(&changelog);
+        Ok(Self {
+            source,
+            changelog,
+            diagnostics,
+            locator: Locator::new(source).with_encoding(encoding),
+        })
+    }
+
+    /// Return the LSP position of an offset.
+    fn position(&self, offset: usize) -> lsp::Position {
+        let point = self.locator.position(&Span::new(offset, offset)).start;
+        lsp::Position::new((point.line - 1) as u32, (point.column - 1) as u32)
+    }
+
+    /// Return the offset of an LSP position.
+    fn offset(&self, position: lsp::Position) -> usize {
+        self.locator
+            .offset(position.line as usize + 1, position.character as usize + 1)
+    }
+
+    fn range(&self, span: Span) -> lsp::Range {
+        lsp::Range::new(self.position(span.start), self.position(span.end))
+    }
+
+    fn diagnostic(&self, diagnostic: &Diagnostic) -> lsp::Diagnostic {
+        lsp::Diagnostic {
+            range: self.range(diagnostic.location.unwrap_or_default()),
+            severity: Some(lsp::DiagnosticSeverity::ERROR),
+            code: Some(lsp::NumberOrString::String(diagnostic.code().to_string())),
+            source: Some("nb".to_string()),
+            message: diagnostic.message(self.source),
+            ..Default::default()
+        }
+    }
+
+    fn diagnostics(&self) -> Vec<lsp::Diagnostic> {
+        self.diagnostics
+            .iter()
+            .map(|diagnostic| self.diagnostic(diagnostic))
+            .collect()
+    }
+
+    fn code_actions(&self, uri: &lsp::Url, range: lsp::Range) -> lsp::CodeActionResponse {
+        let span = Span::new(self.offset(range.start), self.offset(range.end));
+        let mut actions = Vec::new();
+        for diagnostic in &self.diagnostics {
+            if !diagnostic.location.is_some_and(|l| l.overlaps(&span)) {
+                continue;
+            }
+            for fix in &diagnostic.fixes {
+                let edits = fix
+                    .edits
+                    .iter()
+                    .map(|edit| lsp::TextEdit::new(self.range(edit.span), edit.replacement.clone()))
+                    .collect();
+                actions.push(lsp::CodeActionOrCommand::CodeAction(lsp::CodeAction {
+                    title: fix.message.clone(),
+                    kind: Some(lsp::CodeActionKind::QUICKFIX),
+                    diagnostics: Some(vec![self.diagnostic(diagnostic)]),
+                    edit: Some(lsp::WorkspaceEdit {
+                        changes: Some(HashMap::from([(uri.clone(), edits)])),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                }));
+            }
+        }
+        actions
+    }
+
+    /// Explain the rules of the diagnostics at a position.
+    fn hover(&self, position: lsp::Position) -> Option<lsp::Hover> {
+        let offset = self.offset(position);
+        let diagnostics: Vec<&Diagnostic> = self
+            .diagnostics
+            .iter()
+            .filter(|d| {
+                d.location
+                    .is_some_and(|l| l.start <= offset && offset <= l.end)
+            })
+            .collect();
+        let first = diagnostics.first()?;
+        let value = diagnostics
+            .iter()
+            .map(|d| {
+                format!(
+                    "**{}**: {}\n\n{}",
+                    d.code(),
+                    d.message(self.source),
+                    d.rule.doc()
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n\n---\n\n");
+        Some(lsp::Hover {
+            contents: lsp::HoverContents::Markup(lsp::MarkupContent {
+                kind: lsp::MarkupKind::Markdown,
+                value,
+            }),
+            range: first.location.map(|l| self.range(l)),
+        })
+    }
+
+    /// Return a symbol for each release, with children for each change section.
+    fn symbols(&self) -> Vec<lsp::DocumentSymbol> {
+        let unreleased = self.changelog.unreleased.iter().map(|unreleased| {
+            self.symbol(
+                "Unreleased",
+                None,
+                unreleased.heading_span,
+                &unreleased.changes,
+            )
+        });
+        let releases = self.changelog.releases.iter().map(|release| {
+            self.symbol(
+                release.version.value,
+                release.date.map(|d| d.value),
+                release.heading_span,
+                &release.changes,
+            )
+        });
+        unreleased.chain(releases).collect()
+    }
+
+    #[allow(deprecated)]
+    fn symbol(
+        &self,
+        name: &str,
+        detail: Option<&str>,
+        heading_span: Span,
+        changes: &[ParsedChanges],
+    ) -> lsp::DocumentSymbol {
+        let children: Vec<lsp::DocumentSymbol> = changes
+            .iter()
+            .map(|changes| {
+                let end = changes
+                    .items
+                    .iter()
+                    .map(|item| item.span.end)
+                    .fold(changes.heading_span.end, usize::max);
+                lsp::DocumentSymbol {
+                    name: changes.kind.value.to_string(),
+                    detail: None,
+                    kind: lsp::SymbolKind::KEY,
+                    tags: None,
+                    deprecated: None,
+                    range: self.range(Span::new(changes.heading_span.start, end)),
+                    selection_range: self.range(changes.heading_span),
+                    children: None,
+                }
+            })
+            .collect();
+        let end = children
+            .last()
+            .map_or(heading_span.end, |child| self.offset(child.range.end));
+        lsp::DocumentSymbol {
+            name: name.to_string(),
+            detail: detail.map(str::to_string),
+            kind: lsp::SymbolKind::NAMESPACE,
+            tags: None,
+            deprecated: None,
+            range: self.range(Span::new(heading_span.start, end)),
+            selection_range: self.range(heading_span),
+            children: Some(children),
+        }
+    }
+
+    /// Complete change type headings.
+    fn completions(&self, position: lsp::Position) -> Vec<lsp::CompletionItem> {
+        let offset = self.offset(position);
+        let line_start = self.source[..offset].rfind('\n').map_or(0, |i| i + 1);
+        let prefix = &self.source[line_start..offset];
+        let marker = "#".repeat(self.changelog.level + 2);
+        let Some(kind) = prefix.strip_prefix(&marker) else {
+            return vec![];
+        };
+        if kind.starts_with('#') || kind.contains(|c: char| !c.is_alphabetic() && c != ' ') {
+            return vec![];
+        }
+        let range = lsp::Range::new(self.position(line_start), position);
+        CHANGE_TYPES
+            .iter()
+            .map(|kind| {
+                let text = format!("{} {}", marker, kind);
+                lsp::CompletionItem {
+                    label: kind.to_string(),
+                    kind: Some(lsp::CompletionItemKind::KEYWORD),
+                    filter_text: Some(text.clone()),
+                    text_edit: Some(lsp::CompletionTextEdit::Edit(lsp::TextEdit::new(
+                        range, text,
+                    ))),
+                    ..Default::default()
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json::json;
+
+    const SOURCE: &str = "# Changelog
+
+## [Unreleased]
+
+### Foo
+
+* Café
+
+## [1.0.0] - 2025-01-01
+
+* Bar
+
+[unreleased]: https://example.org/
+[1.0.0]: https://example.org/
+";
+
+    fn uri() -> lsp::Url {
+        lsp::Url::parse("untitled:CHANGELOG.md").unwrap()
+    }
+
+    fn document(source: &str) -> Document {
+        Document::open(&uri(), source.to_string()).0
+    }
+
+    #[test]
+    fn test_position() {
+        let source = "# Café 😀\n\n## Foo";
+        let document = document(source);
+        let analysis = Analysis::new(&document, Encoding::Utf16).unwrap();
+        let offset = source.find("😀").unwrap() + "😀".len();
+        assert_eq!(analysis.position(offset), lsp::Position::new(0, 9));
+        assert_eq!(analysis.offset(lsp::Position::new(0, 9)), offset);
+        assert_eq!(analysis.offset(lsp::Position::new(2, 3)), source.len() - 3);
+        assert_eq!(analysis.offset(lsp::Position::new(2, 6)), source.len());
+        assert_eq!(analysis.offset(lsp::Position::new(9, 0)), source.len());
+
+        let analysis = Analysis::new(&document, Encoding::Utf8).unwrap();
+        assert_eq!(analysis.position(offset), lsp::Position::new(0, 12));
+        assert_eq!(analysis.offset(lsp::Position::new(0, 12)), offset);
+    }
+
+    #[test]
+    fn test_analysis() {
+        let uri = uri();
+        let document = document(SOURCE);
+        let analysis = Analysis::new(&document, Encoding::Utf16).unwrap();
+        let codes: Vec<_> = analysis
+            .diagnostics()
+            .into_iter()
+            .map(|d| (d.code, d.range.start.line))
+            .collect();
+        assert_eq!(
+            codes,
+            vec![
+                (Some(lsp::NumberOrString::String("E103".to_string())), 4),
+                (Some(lsp::NumberOrString::String("E106".to_string())), 10),
+            ]
+        );
+
+        let actions = analysis.code_actions(
+            &uri,
+            lsp::Range::new(lsp::Position::new(10, 0), lsp::Position::new(10, 0)),
+        );
+        assert_eq!(actions.len(), 6);
+        let lsp::CodeActionOrCommand::CodeAction(action) = &actions[0] else {
+            panic!("expected a code action");
+        };
+        assert_eq!(action.title, "Add a `### Added` heading");
+
+        let hover = analysis.hover(lsp::Position::new(4, 5)).unwrap();
+        let lsp::HoverContents::Markup(content) = hover.contents else {
+            panic!("expected markup");
+        };
+        assert!(
+            content
+                .value
+                .starts_with("**E103**: Invalid change type `Foo`")
+        );
+        assert!(analysis.hover(lsp::Position::new(0, 0)).is_none());
+
+        let symbols = analysis.symbols();
+        assert_eq!(
+            symbols
+                .iter()
+                .map(|s| (s.name.as_str(), s.children.as_ref().unwrap().len()))
+                .collect::<Vec<_>>(),
+            vec![("Unreleased", 1), ("1.0.0", 0)]
+        );
+        assert_eq!(
+            symbols[0].range,
+            lsp::Range::new(lsp::Position::new(2, 0), lsp::Position::new(6, 6))
+        );
+    }
+
+    #[test]
+    fn test_completions() {
+        let source =
+            "# Changelog\n\n## [Unreleased]\n\n### Ad\n\n[unreleased]: https://example.org/\n";
+        let document = document(source);
+        let analysis = Analysis::new(&document, Encoding::Utf16).unwrap();
+        let completions = analysis.completions(lsp::Position::new(4, 6));
+        assert_eq!(
+            completions
+                .iter()
+                .map(|c| c.label.as_str())
+                .collect::<Vec<_>>(),
+            CHANGE_TYPES
+        );
+        assert_eq!(
+            completions[0].text_edit,
+            Some(lsp::CompletionTextEdit::Edit(lsp::TextEdit::new(
+                lsp::Range::new(lsp::Position::new(4, 0), lsp::Position::new(4, 6)),
+                "### Added".to_string()
+            )))
+        );
+        assert!(analysis.completions(lsp::Position::new(2, 2)).is_empty());
+        assert!(analysis.completions(lsp::Position::new(0, 1)).is_empty());
+    }
+
+    #[test]
+    fn test_document_config_error() {
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(dir.path().join("nb.toml"), "[lint]\nselect = 1\n").unwrap();
+        let uri = lsp::Url::from_file_path(dir.path().join("CHANGELOG.md")).unwrap();
+        let (document, error) = Document::open(&uri, SOURCE.to_string());
+        assert!(matches!(error, Some(Error::File(..))), "{:?}", error);
+        // The document falls back to the default configuration.
+        let analysis = Analysis::new(&document, Encoding::Utf16).unwrap();
+        assert_eq!(analysis.diagnostics().len(), 2);
+    }
+
+    #[test]
+    fn test_server() {
+        let (server, client) = Connection::memory();
+        let thread = std::thread::spawn(move || run(&server));
+        let request = |id: i32, method: &str, params: serde_json::Value| {
+            client
+                .sender
+                .send(Request::new(id.into(), method.to_string(), params).into())
+                .unwrap();
+            match client.receiver.recv().unwrap() {
+                Message::Response(response) => response,
+                message => panic!("unexpected message: {:?}", message),
+            }
+        };
+        let notify = |method: &str, params: serde_json::Value| {
+            client
+                .sender
+                .send(Notification::new(method.to_string(), params).into())
+                .unwrap();
+        };
+
+        let response = request(
+            1,
+            "initialize",
+            json!({"capabilities": {"general": {"positionEncodings": ["utf-32", "utf-16"]}}}),
+        );
+        let capabilities = &response.result.unwrap()["capabilities"];
+        assert_eq!(capabilities["hoverProvider"], json!(true));
+        assert_eq!(capabilities["positionEncoding"], json!("utf-32"));
+        notify("initialized", json!({}));
+        notify(
+            "textDocument/didOpen",
+            json!({
+                "textDocument": {
+                    "uri": uri(),
+                    "languageId": "markdown",
+                    "version": 1,
+                    "text": SOURCE,
+                }
+            }),
+        );
+        let Message::Notification(notification) = client.receiver.recv().unwrap() else {
+            panic!("expected a notification");
+        };
+        assert_eq!(notification.method, "textDocument/publishDiagnostics");
+        assert_eq!(
+            notification.params["diagnostics"].as_array().unwrap().len(),
+            2
+        );
+
+        let response = request(
+            2,
+            "textDocument/documentSymbol",
+            json!({"textDocument": {"uri": uri()}}),
+        );
+        assert_eq!(response.result.unwrap().as_array().unwrap().len(), 2);
+
+        let response = request(3, "shutdown", json!(null));
+        assert!(response.error.is_none());
+        notify("exit", json!(null));
+        thread.join().unwrap().unwrap();
+    }
+}
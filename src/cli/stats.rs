@@ -0,0 +1,254 @@
+//! Compute statistics about the releases in a changelog.
+use std::collections::BTreeMap;
+use std::io::Write;
+
+use serde::Serialize;
+
+use crate::Date;
+use crate::changelog::traits::{Changelog, Changes, Release};
+use crate::linter::CHANGE_TYPES;
+
+use super::report::DataFormat;
+
+/// Statistics about the releases in a changelog.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Stats {
+    pub releases: usize,
+    pub yanked: usize,
+    /// The number of releases per year, for releases with a valid date.
+    pub releases_per_year: BTreeMap<u16, usize>,
+    /// The median number of days between consecutive releases with a valid date.
+    pub median_days_between_releases: Option<f64>,
+    /// The longest time between consecutive releases with a valid date.
+    pub longest_gap: Option<Gap>,
+    /// The number of releases with `Security` entries.
+    pub security_releases: usize,
+    /// The share of releases with `Security` entries, from 0 to 1.
+    pub security_share: Option<f64>,
+    /// The number of entries per change type.
+    pub entries: BTreeMap<String, usize>,
+}
+
+/// The time between two releases.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Gap {
+    pub from: Date,
+    pub to: Date,
+    pub days: i64,
+}
+
+/// Compute statistics about the releases in `changelog`. The unreleased section is ignored.
+pub fn stats<C: Changelog>(changelog: &C) -> Stats {
+    let releases = changelog.releases();
+    let mut dates: Vec<Date> = releases
+        .iter()
+        .filter_map(|r| r.date()?.parse().ok())
+        .collect();
+    dates.sort();
+    let mut releases_per_year = BTreeMap::new();
+    for date in &dates {
+        *releases_per_year.entry(date.year()).or_default() += 1;
+    }
+    let gaps: Vec<Gap> = dates
+        .windows(2)
+        .map(|pair| Gap {
+            from: pair[0],
+            to: pair[1],
+            days: pair[1].days() - pair[0].days(),
+        })
+        .collect();
+    let mut days: Vec<i64> = gaps.iter().map(|gap| gap.days).collect();
+    days.sort();
+    let median_days_between_releases = match days.len() {
+        0 => None,
+        n if n % 2 == 1 => Some(days[n / 2] as f64),
+        n => Some((days[n / 2 - 1] + days[n / 2]) as f64 / 2.0),
+    };
+    // Keep the earliest of equal gaps.
+    let longest_gap = gaps.into_iter().rev().max_by_key(|gap| gap.days);
+    let mut entries = BTreeMap::new();
+    for release in releases {
+        for changes in release.changes() {
+            *entries.entry(changes.kind().to_string()).or_default() += changes.items().count();
+        }
+    }
+    let security_releases = releases
+        .iter()
+        .filter(|r| {
+            r.changes()
+                .iter()
+                .any(|c| c.kind() == "Security" && c.items().next().is_some())
+        })
+        .count();
+    Stats {
+        releases: releases.len(),
+        yanked: releases.iter().filter(|r| r.yanked()).count(),
+        releases_per_year,
+        median_days_between_releases,
+        longest_gap,
+        security_releases,
+        security_share: (!releases.is_empty())
+            .then(|| security_releases as f64 / releases.len() as f64),
+        entries,
+    }
+}
+
+/// Write the statistics in `format`.
+pub fn report<W: Write>(w: &mut W, stats: &Stats, format: DataFormat) -> std::io::Result<()> {
+    if format == DataFormat::Json {
+        serde_json::to_writer_pretty(&mut *w, stats)?;
+        return writeln!(w);
+    }
+    let none = || "-".to_string();
+    let rows = [
+        ("Releases", stats.releases.to_string()),
+        ("Yanked releases", stats.yanked.to_string()),
+        (
+            "Median days between releases",
+            stats
+                .median_days_between_releases
+                .map_or_else(none, |days| days.to_string()),
+        ),
+        (
+            "Longest gap without a release",
+            stats.longest_gap.as_ref().map_or_else(none, |gap| {
+                format!("{} days ({} to {})", gap.days, gap.from, gap.to)
+            }),
+        ),
+        (
+            "Releases with security fixes",
+            stats.security_share.map_or_else(none, |share| {
+                format!(
+                    "{:.0}% ({} of {})",
+                    share * 100.0,
+                    stats.security_releases,
+                    stats.releases
+                )
+            }),
+        ),
+    ];
+    for (key, value) in rows.iter() {
+        writeln!(w, "{:30} {}", key, value)?;
+    }
+    let years: Vec<(String, String)> = stats
+        .releases_per_year
+        .iter()
+        .map(|(year, count)| (year.to_string(), count.to_string()))
+        .collect();
+    if !years.is_empty() {
+        writeln!(w)?;
+        table(w, ("Year", "Releases"), &years)?;
+    }
+    // List change types in the order of Keep a Changelog, then other types.
+    let mut entries: Vec<(&String, &usize)> = stats.entries.iter().collect();
+    entries.sort_by_key(|(kind, _)| {
+        CHANGE_TYPES
+            .iter()
+            .position(|t| t == kind)
+            .unwrap_or(CHANGE_TYPES.len())
+    });
+    let entries: Vec<(String, String)> = entries
+        .into_iter()
+        .map(|(kind, count)| (kind.clone(), count.to_string()))
+        .collect();
+    if !entries.is_empty() {
+        writeln!(w)?;
+        table(w, ("Change type", "Entries"), &entries)?;
+    }
+    Ok(())
+}
+
+/// Write a table of two columns with a header, with the second column aligned to the right.
+fn table<W: Write, K: AsRef<str>>(
+    w: &mut W,
+    header: (&str, &str),
+    rows: &[(K, String)],
+) -> std::io::Result<()> {
+    let all = [header].into_iter().chain(
+        rows.iter()
+            .map(|(key, value)| (key.as_ref(), value.as_str())),
+    );
+    let (key_width, value_width) = all.clone().fold((0, 0), |(k, v), (key, value)| {
+        (k.max(key.chars().count()), v.max(value.chars().count()))
+    });
+    for (key, value) in all {
+        writeln!(w, "{:key_width$}  {:>value_width$}", key, value)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHANGELOG: &str = "\
+# Changelog
+
+## [1.2.0] - 2025-03-01
+
+### Security
+
+* Fix foo
+
+## [1.1.0] - 2025-01-30 [YANKED]
+
+### Added
+
+* Add foo
+* Add bar
+
+### Fixed
+
+* Fix bar
+
+## [1.0.0] - 2024-12-01
+
+### Added
+
+* Add baz
+
+[1.2.0]: https://example.org/compare/v1.1.0...v1.2.0
+[1.1.0]: https://example.org/compare/v1.0.0...v1.1.0
+[1.0.0]: https://example.org/releases/tag/v1.0.0
+";
+
+    #[test]
+    fn test_stats() {
+        let stats = stats(&crate::parse(CHANGELOG));
+        assert_eq!(stats.releases, 3);
+        assert_eq!(stats.yanked, 1);
+        assert_eq!(stats.median_days_between_releases, Some(45.0));
+        assert_eq!(
+            stats.longest_gap,
+            Some(Gap {
+                from: Date::new(2024, 12, 1).unwrap(),
+                to: Date::new(2025, 1, 30).unwrap(),
+                days: 60,
+            })
+        );
+        let mut output = Vec::new();
+        report(&mut output, &stats, DataFormat::Text).unwrap();
+        insta::assert_snapshot!(String::from_utf8(output).unwrap());
+        insta::assert_yaml_snapshot!(stats);
+    }
+
+    #[test]
+    fn test_stats_empty() {
+        let stats = stats(&crate::parse("# Changelog\n"));
+        assert_eq!(stats.releases, 0);
+        assert_eq!(stats.median_days_between_releases, None);
+        assert_eq!(stats.security_share, None);
+        let mut output = Vec::new();
+        report(&mut output, &stats, DataFormat::Text).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "\
+Releases                       0
+Yanked releases                0
+Median days between releases   -
+Longest gap without a release  -
+Releases with security fixes   -
+"
+        );
+    }
+}
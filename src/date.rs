@@ -0,0 +1,210 @@
+//! Calendar dates.
+use std::fmt;
+use std::str::FromStr;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// A calendar date in the format `YYYY-MM-DD`.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Date {
+    year: u16,
+    month: u8,
+    day: u8,
+}
+
+/// An error parsing a [`Date`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ParseDateError;
+
+impl Date {
+    /// Create a new date.
+    ///
+    /// Return `None` if the date does not exist (e.g. February 30) or the year is after 9999.
+    pub fn new(year: u16, month: u8, day: u8) -> Option<Self> {
+        if year > 9999 || !(1..=12).contains(&month) || day < 1 || day > days_in_month(year, month)
+        {
+            return None;
+        }
+        Some(Self { year, month, day })
+    }
+
+    /// Return the current date in UTC.
+    #[cfg(not(all(target_arch = "wasm32", target_os = "unknown", feature = "wasm")))]
+    pub fn today() -> Self {
+        use std::time::{SystemTime, UNIX_EPOCH};
+        let seconds = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        Self::from_days((seconds / 86_400) as i64)
+    }
+
+    /// Return the current date in UTC.
+    ///
+    /// `SystemTime::now` panics on `wasm32-unknown-unknown`, so ask JavaScript for the time.
+    #[cfg(all(target_arch = "wasm32", target_os = "unknown", feature = "wasm"))]
+    pub fn today() -> Self {
+        let milliseconds = js_sys::Date::now();
+        Self::from_days((milliseconds / 86_400_000.0).floor() as i64)
+    }
+
+    /// Return the year.
+    pub fn year(&self) -> u16 {
+        self.year
+    }
+
+    /// Return the month, from 1 to 12.
+    pub fn month(&self) -> u8 {
+        self.month
+    }
+
+    /// Return the day of the month, from 1 to 31.
+    pub fn day(&self) -> u8 {
+        self.day
+    }
+
+    /// Return the number of days since 1970-01-01.
+    pub fn days(&self) -> i64 {
+        // https://howardhinnant.github.io/date_algorithms.html#days_from_civil
+        let (month, day) = (self.month as i64, self.day as i64);
+        let year = self.year as i64 - if month <= 2 { 1 } else { 0 };
+        let era = year.div_euclid(400);
+        let yoe = year - era * 400;
+        let doy = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day - 1;
+        let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+        era * 146_097 + doe - 719_468
+    }
+
+    /// Return the date `days` days after 1970-01-01.
+    pub(crate) fn from_days(days: i64) -> Self {
+        // https://howardhinnant.github.io/date_algorithms.html#civil_from_days
+        let z = days + 719_468;
+        let era = z.div_euclid(146_097);
+        let doe = z - era * 146_097;
+        let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = (doy - (153 * mp + 2) / 5 + 1) as u8;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 } as u8;
+        let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+        Self {
+            year: year.clamp(0, 9999) as u16,
+            month,
+            day,
+        }
+    }
+}
+
+fn is_leap_year(year: u16) -> bool {
+    year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400))
+}
+
+fn days_in_month(year: u16, month: u8) -> u8 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+impl FromStr for Date {
+    type Err = ParseDateError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let bytes = s.as_bytes();
+        if bytes.len() != 10 || bytes[4] != b'-' || bytes[7] != b'-' {
+            return Err(ParseDateError);
+        }
+        let digits = |range: std::ops::Range<usize>| -> Result<u16, ParseDateError> {
+            let part = &bytes[range];
+            if !part.iter().all(u8::is_ascii_digit) {
+                return Err(ParseDateError);
+            }
+            Ok(part.iter().fold(0, |n, b| n * 10 + (b - b'0') as u16))
+        };
+        let year = digits(0..4)?;
+        let month = digits(5..7)? as u8;
+        let day = digits(8..10)? as u8;
+        Self::new(year, month, day).ok_or(ParseDateError)
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+impl std::error::Error for ParseDateError {}
+
+impl fmt::Display for ParseDateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid date, expected YYYY-MM-DD")
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for Date {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for Date {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_str() {
+        assert_eq!("2025-01-31".parse(), Ok(Date::new(2025, 1, 31).unwrap()));
+        assert_eq!("2024-02-29".parse(), Ok(Date::new(2024, 2, 29).unwrap()));
+        assert_eq!("2000-02-29".parse(), Ok(Date::new(2000, 2, 29).unwrap()));
+        for s in [
+            "2025-13-45",
+            "2025-02-3x",
+            "2025-00-01",
+            "2025-01-00",
+            "2025-04-31",
+            "2025-02-29",
+            "1900-02-29",
+            "2025-1-01",
+            "2025/01/01",
+            "+025-01-01",
+            "20250101",
+            "2025-01-01T00:00:00Z",
+        ] {
+            assert_eq!(s.parse::<Date>(), Err(ParseDateError), "{}", s);
+        }
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Date::new(987, 6, 5).unwrap().to_string(), "0987-06-05");
+    }
+
+    #[test]
+    fn test_days() {
+        assert_eq!(Date::new(1970, 1, 1).unwrap().days(), 0);
+        assert_eq!(Date::new(2000, 3, 1).unwrap().days(), 11_017);
+        assert_eq!(Date::new(1969, 12, 31).unwrap().days(), -1);
+        for days in [-1, 0, 59, 11_016, 11_017, 20_000, 2_932_896] {
+            assert_eq!(Date::from_days(days).days(), days);
+        }
+    }
+}
@@ -8,6 +8,9 @@ use serde::{Deserialize, Serialize};
 use crate::rule::Rule;
 use crate::span::{Locator, Position, Ranged, Span};
 
+#[cfg(any(feature = "cli", feature = "capi", feature = "wasm"))]
+pub(crate) use json::JsonDiagnostic;
+
 /// A rule violation.
 ///
 /// A `Diagnostic` can hold either a [`Span`] or [`Position`].
@@ -24,6 +27,39 @@ pub struct Diagnostic<L = Span> {
     pub location: Option<L>,
     /// The source path, used in reporting.
     pub path: Option<PathBuf>,
+    /// The text to substitute in the message if the location does not contain it (e.g. the name of
+    /// a git tag).
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub argument: Option<String>,
+    /// Suggested fixes for the violation, if any.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub fixes: Vec<Fix>,
+}
+
+/// A suggested fix for a [`Diagnostic`].
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct Fix {
+    /// A short description of the fix.
+    pub message: String,
+    /// The edits to apply to the source document.
+    pub edits: Vec<Edit>,
+}
+
+/// A replacement of a span in the source document.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct Edit {
+    /// The span to replace. An empty span inserts text.
+    pub span: Span,
+    /// The replacement text.
+    pub replacement: String,
 }
 
 impl Diagnostic {
@@ -33,11 +69,57 @@ impl Diagnostic {
             rule,
             location,
             path: None,
+            argument: None,
+            fixes: Vec::new(),
+        }
+    }
+}
+
+impl Fix {
+    /// Create a new fix.
+    pub fn new<S: Into<String>>(message: S, edits: Vec<Edit>) -> Self {
+        Self {
+            message: message.into(),
+            edits,
+        }
+    }
+
+    /// Apply the fix to the source document.
+    pub fn apply(&self, source: &str) -> String {
+        let mut edits: Vec<&Edit> = self.edits.iter().collect();
+        edits.sort_by_key(|edit| std::cmp::Reverse(edit.span));
+        let mut s = source.to_string();
+        for edit in edits {
+            s.replace_range(edit.span.range(), &edit.replacement);
+        }
+        s
+    }
+}
+
+impl Edit {
+    /// Create an edit that inserts `text` at `offset`.
+    pub fn insert<S: Into<String>>(offset: usize, text: S) -> Self {
+        Self {
+            span: Span::new(offset, offset),
+            replacement: text.into(),
         }
     }
 }
 
 impl<L> Diagnostic<L> {
+    /// Set the suggested fixes for this diagnostic.
+    pub fn with_fixes(self, fixes: Vec<Fix>) -> Self {
+        Self { fixes, ..self }
+    }
+
+    /// Set the text to substitute in the message.
+    pub fn with_argument<S: Into<String>>(self, argument: S) -> Self {
+        Self {
+            argument: Some(argument.into()),
+            ..self
+        }
+    }
+
     /// Return the diagnostic rule code.
     pub fn code(&self) -> &str {
         self.rule.code()
@@ -51,6 +133,8 @@ impl<Span: Ranged<usize>> Diagnostic<Span> {
             rule: self.rule,
             location: self.location.map(|s| locator.position(&s.range())),
             path: self.path,
+            argument: self.argument,
+            fixes: self.fixes,
         }
     }
 }
@@ -58,6 +142,9 @@ impl<Span: Ranged<usize>> Diagnostic<Span> {
 impl<L: Ranged<usize>> Diagnostic<L> {
     /// Return a formatted message.
     pub fn message(&self, source: &str) -> String {
+        if let Some(argument) = &self.argument {
+            return self.rule.message().replace("{}", argument);
+        }
         let range = self.location.as_ref().map(|l| l.range());
         match range {
             Some(range) => {
@@ -74,6 +161,7 @@ impl<L: Ranged<usize>> Diagnostic<L> {
     }
 
     /// Return the unist Position of the diagnostic.
+    #[cfg(any(feature = "cli", feature = "capi", feature = "wasm"))]
     pub(crate) fn position(&self, locator: &Locator) -> Option<Position> {
         self.location.as_ref().map(|l| locator.position(&l.range()))
     }
@@ -91,6 +179,75 @@ impl Diagnostic<Position> {
     }
 }
 
+/// The JSON shape of diagnostics, shared by `nb lint --output-format json` and the language
+/// bindings.
+#[cfg(any(feature = "cli", feature = "capi", feature = "wasm"))]
+mod json {
+    use std::path::Path;
+
+    use serde::Serialize;
+
+    use super::Diagnostic;
+    use crate::span::{Locator, Position};
+
+    /// A located diagnostic.
+    #[derive(Clone, Debug, PartialEq, Serialize)]
+    pub(crate) struct JsonDiagnostic {
+        pub code: String,
+        pub position: Option<Position>,
+        pub path: Option<String>,
+        pub message: String,
+        #[serde(skip_serializing_if = "Vec::is_empty")]
+        pub fixes: Vec<JsonFix>,
+    }
+
+    /// A fix with located edits.
+    #[derive(Clone, Debug, PartialEq, Serialize)]
+    pub(crate) struct JsonFix {
+        pub message: String,
+        pub edits: Vec<JsonEdit>,
+    }
+
+    /// A located edit, with columns in the same encoding as the diagnostic.
+    #[derive(Clone, Debug, PartialEq, Serialize)]
+    pub(crate) struct JsonEdit {
+        pub position: Position,
+        pub replacement: String,
+    }
+
+    impl JsonDiagnostic {
+        /// Locate a diagnostic and its fixes in `source` with `locator`.
+        pub(crate) fn new(
+            diagnostic: &Diagnostic<Position>,
+            source: &str,
+            locator: &Locator,
+            path: Option<&Path>,
+        ) -> Self {
+            Self {
+                code: diagnostic.code().to_string(),
+                position: diagnostic.position(locator),
+                path: path.map(|p| p.to_string_lossy().to_string()),
+                message: diagnostic.message(source),
+                fixes: diagnostic
+                    .fixes
+                    .iter()
+                    .map(|fix| JsonFix {
+                        message: fix.message.clone(),
+                        edits: fix
+                            .edits
+                            .iter()
+                            .map(|edit| JsonEdit {
+                                position: locator.position(&edit.span),
+                                replacement: edit.replacement.clone(),
+                            })
+                            .collect(),
+                    })
+                    .collect(),
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -102,7 +259,9 @@ mod tests {
             Diagnostic {
                 rule: Rule::MissingTitle,
                 location: None,
-                path: None
+                path: None,
+                argument: None,
+                fixes: vec![],
             }
         );
         assert_eq!(
@@ -110,7 +269,9 @@ mod tests {
             Diagnostic {
                 rule: Rule::MissingTitle,
                 location: Some(Span::default()),
-                path: None
+                path: None,
+                argument: None,
+                fixes: vec![],
             }
         );
     }
@@ -135,5 +296,48 @@ mod tests {
             diagnostic.message(source),
             Rule::DuplicateTitle.message().replace("{}", "Changelog")
         );
+
+        let diagnostic = Diagnostic::new(Rule::MissingRelease, None).with_argument("v1.0.0");
+        assert_eq!(
+            diagnostic.message(source),
+            Rule::MissingRelease.message().replace("{}", "v1.0.0")
+        );
+    }
+
+    #[test]
+    fn test_fix_apply() {
+        let source = "## [1.0.0]\n\n* Add foo\n";
+        let fix = Fix::new(
+            "Add heading",
+            vec![
+                Edit::insert(12, "### Added\n\n"),
+                Edit {
+                    span: Span::new(14, 21),
+                    replacement: "Add bar".to_string(),
+                },
+            ],
+        );
+        assert_eq!(fix.apply(source), "## [1.0.0]\n\n### Added\n\n* Add bar\n");
+    }
+
+    #[cfg(any(feature = "cli", feature = "capi", feature = "wasm"))]
+    #[test]
+    fn test_json_diagnostic() {
+        use crate::span::Encoding;
+
+        let source = "* 😀 [x]\n";
+        let locator = Locator::new(source).with_encoding(Encoding::Utf16);
+        let diagnostic = Diagnostic::new(Rule::UndefinedLinkReference, Some(Span::new(7, 10)))
+            .with_fixes(vec![Fix::new(
+                "Remove the link",
+                vec![Edit::insert(7, "x")],
+            )])
+            .locate(&locator);
+        let json = JsonDiagnostic::new(&diagnostic, source, &locator, None);
+        let position = json.position.unwrap();
+        assert_eq!(position.start.column, 6);
+        assert_eq!(json.fixes[0].edits[0].position.start, position.start);
+        assert_eq!(json.fixes[0].edits[0].replacement, "x");
+        assert_eq!(json.path, None);
     }
 }
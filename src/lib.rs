@@ -184,8 +184,13 @@
 //! # Features
 //!
 //! * `serde`: Add support for serializing key types with [`serde`](https://serde.rs/).
+//! * `capi`: Build a C API. See [`capi`].
 //! * `cli`: Build the CLI.
+//! * `wasm`: Build a JavaScript API with [`wasm-bindgen`](https://rustwasm.github.io/wasm-bindgen/).
+#[cfg(any(feature = "capi", feature = "wasm"))]
+pub(crate) mod api;
 pub(crate) mod ast;
+pub(crate) mod date;
 pub(crate) mod diagnostic;
 pub(crate) mod linter;
 pub(crate) mod parser;
@@ -193,13 +198,20 @@ pub mod prelude;
 pub(crate) mod rule;
 pub(crate) mod ruleset;
 pub mod span;
+pub(crate) mod vcs;
 
+#[cfg(feature = "capi")]
+pub mod capi;
 pub mod changelog;
 #[cfg(feature = "cli")]
 pub mod cli;
+#[cfg(feature = "wasm")]
+pub mod wasm;
 
-pub use diagnostic::Diagnostic;
+pub use date::Date;
+pub use diagnostic::{Diagnostic, Edit, Fix};
 pub use linter::Linter;
-pub use parser::parse;
+pub use parser::{Layout, Parser, Region, parse};
 pub use rule::Rule;
 pub use ruleset::RuleSet;
+pub use vcs::{Commits, Tag};
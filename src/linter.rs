@@ -3,10 +3,12 @@ use std::path::PathBuf;
 
 use crate::changelog::parsed;
 use crate::changelog::traits::*;
+use crate::date::Date;
 use crate::diagnostic::Diagnostic;
 use crate::rule::Rule;
 use crate::ruleset::RuleSet;
 use crate::span::Span;
+use crate::vcs::{Commits, Tag};
 
 #[macro_use]
 mod macros;
@@ -15,6 +17,8 @@ mod check;
 mod checks;
 
 use check::Check;
+#[cfg(feature = "cli")]
+pub(crate) use checks::CHANGE_TYPES;
 
 macro_rules! checks {
     ($($check:ty),* $(,)?) => {
@@ -27,16 +31,49 @@ macro_rules! checks {
 pub struct Linter<'a> {
     ruleset: &'a RuleSet,
     filename: Option<PathBuf>,
+    today: Option<Date>,
+    tags: Option<Vec<Tag>>,
+    date_tolerance: u32,
+    unreleased_commits: Option<Commits>,
+    changes: Option<Vec<Span>>,
+    released_versions: Option<Vec<String>>,
 }
 
-#[derive(Default)]
-pub(crate) struct Context {
+pub(crate) struct Context<'a> {
     diagnostics: Vec<Diagnostic>,
+    /// The current date, used to check release dates.
+    pub(crate) today: Date,
+    /// Release tags, if tags are available.
+    pub(crate) tags: Option<&'a [Tag]>,
+    /// The maximum number of days between a release date and its tag date.
+    pub(crate) date_tolerance: u32,
+    /// Commits since the latest release, if available.
+    pub(crate) unreleased_commits: Option<&'a Commits>,
+    /// Changed spans, if only changes are checked.
+    pub(crate) changes: Option<&'a [Span]>,
+    /// The versions released before the changes, if known.
+    pub(crate) released_versions: Option<&'a [String]>,
 }
 
-impl Context {
+impl<'a> Context<'a> {
+    fn new(today: Date) -> Self {
+        Self {
+            diagnostics: Vec::new(),
+            today,
+            tags: None,
+            date_tolerance: 0,
+            unreleased_commits: None,
+            changes: None,
+            released_versions: None,
+        }
+    }
+
     fn report(&mut self, rule: Rule, span: Option<Span>) {
-        self.diagnostics.push(Diagnostic::new(rule, span));
+        self.report_diagnostic(Diagnostic::new(rule, span));
+    }
+
+    fn report_diagnostic(&mut self, diagnostic: Diagnostic) {
+        self.diagnostics.push(diagnostic);
     }
 }
 
@@ -46,6 +83,12 @@ impl<'a> Linter<'a> {
         Self {
             ruleset,
             filename: None,
+            today: None,
+            tags: None,
+            date_tolerance: 0,
+            unreleased_commits: None,
+            changes: None,
+            released_versions: None,
         }
     }
 
@@ -57,13 +100,69 @@ impl<'a> Linter<'a> {
         }
     }
 
+    /// Set the current date, used to check for releases more than one day in the future.
+    ///
+    /// Defaults to the current date in UTC.
+    pub fn with_today(self, today: Option<Date>) -> Self {
+        Self { today, ..self }
+    }
+
+    /// Set the release tags to check releases against.
+    ///
+    /// The linter only checks releases against tags if tags are set.
+    pub fn with_tags(self, tags: Option<Vec<Tag>>) -> Self {
+        Self { tags, ..self }
+    }
+
+    /// Set the maximum number of days between a release date and the date of its tag.
+    pub fn with_date_tolerance(self, days: u32) -> Self {
+        Self {
+            date_tolerance: days,
+            ..self
+        }
+    }
+
+    /// Set the commits since the latest release.
+    ///
+    /// The linter only checks the unreleased section against commits if commits are set.
+    pub fn with_unreleased_commits(self, commits: Option<Commits>) -> Self {
+        Self {
+            unreleased_commits: commits,
+            ..self
+        }
+    }
+
+    /// Set the changed spans in the changelog.
+    ///
+    /// If changes are set, the linter only reports diagnostics that overlap a change, and checks
+    /// for changes to released sections. An empty span marks deleted content.
+    pub fn with_changes(self, changes: Option<Vec<Span>>) -> Self {
+        Self { changes, ..self }
+    }
+
+    /// Set the versions that were released before the changes.
+    ///
+    /// Releases with other versions are new, so changes to them are not reported as changes to
+    /// released sections. If versions are not set, all releases are treated as released.
+    pub fn with_released_versions(self, versions: Option<Vec<String>>) -> Self {
+        Self {
+            released_versions: versions,
+            ..self
+        }
+    }
+
     /// Lint a changelog.
     pub fn lint(&self, changelog: &parsed::ParsedChangelog) -> Vec<Diagnostic> {
         let mut checks: Vec<_> = checks()
             .into_iter()
             .filter(|check| self.ruleset.is_enabled(check.rule()))
             .collect();
-        let mut context = Context::default();
+        let mut context = Context::new(self.today.unwrap_or_else(Date::today));
+        context.tags = self.tags.as_deref();
+        context.date_tolerance = self.date_tolerance;
+        context.unreleased_commits = self.unreleased_commits.as_ref();
+        context.changes = self.changes.as_deref();
+        context.released_versions = self.released_versions.as_deref();
         for check in checks.iter_mut() {
             check.visit_changelog(&mut context, changelog);
             if let Some(unreleased) = changelog.unreleased() {
@@ -90,12 +189,25 @@ impl<'a> Linter<'a> {
         context
             .diagnostics
             .into_iter()
+            .filter(|diagnostic| self.is_changed(diagnostic))
             .map(|mut diagnostic| {
                 diagnostic.path = self.filename.clone();
                 diagnostic
             })
             .collect()
     }
+
+    /// Return `true` if the diagnostic overlaps a change, or if all content is checked.
+    ///
+    /// Diagnostics without a location do not refer to any content, so they are always reported.
+    fn is_changed(&self, diagnostic: &Diagnostic) -> bool {
+        match (&self.changes, diagnostic.location) {
+            (Some(changes), Some(location)) => {
+                changes.iter().any(|change| change.overlaps(&location))
+            }
+            _ => true,
+        }
+    }
 }
 
 impl<'a> Default for Linter<'a> {
@@ -118,13 +230,23 @@ fn checks() -> Vec<Box<dyn Check>> {
         checks::EmptySection,
         checks::UnknownChangeType,
         checks::DuplicateChangeType,
+        checks::UnexpectedContent,
+        checks::MissingChangeType,
         // E200 Release
         checks::InvalidReleaseOrder,
         checks::DuplicateVersion,
         checks::MissingDate,
         checks::InvalidDate,
         checks::InvalidYanked,
+        checks::FutureDate,
         // E300 Links
         checks::UndefinedLinkReference,
+        // E400 Git
+        checks::MissingTag,
+        checks::MissingRelease,
+        checks::TagDateMismatch,
+        checks::MissingUnreleasedChanges,
+        checks::ModifiedRelease,
+        checks::RemovedRelease,
     ]
 }
@@ -1,10 +1,12 @@
 mod content;
+mod git;
 mod links;
 pub(crate) mod preamble;
 mod releases;
 mod structure;
 
 pub use content::*;
+pub use git::*;
 pub use links::*;
 pub use releases::*;
 pub use structure::*;
@@ -1,33 +1,71 @@
 use std::collections::HashSet;
 
 use crate::changelog::parsed;
+use crate::diagnostic::{Diagnostic, Edit, Fix};
 use crate::linter::Check;
 use crate::rule::Rule;
 
 use super::preamble::*;
 
+/// The change types defined by Keep a Changelog.
+pub(crate) const CHANGE_TYPES: [&str; 6] = [
+    "Added",
+    "Changed",
+    "Deprecated",
+    "Removed",
+    "Fixed",
+    "Security",
+];
+
 invalid_span!(InvalidTitle);
 
 invalid_span!(InvalidSectionHeading);
 
 #[derive(Default)]
-pub struct EmptySection;
+pub struct EmptySection {
+    /// The heading spans of releases whose changes are in lists without a change type.
+    missing_change_type: HashSet<Span>,
+}
 
-// TODO: Store better spans for these headings.
 impl Check for EmptySection {
     fn rule(&self) -> Rule {
         Rule::EmptySection
     }
 
+    fn visit_changelog(&mut self, _context: &mut Context, changelog: &parsed::ParsedChangelog) {
+        // A release with only a list of changes is reported as missing a change type, not empty.
+        self.missing_change_type.clear();
+        let lists: Vec<Span> = changelog
+            .invalid_spans
+            .iter()
+            .filter_map(|span| match span {
+                parsed::InvalidSpan::MissingChangeType(s) => Some(*s),
+                _ => None,
+            })
+            .collect();
+        for (i, release) in changelog.releases.iter().enumerate() {
+            let end = changelog
+                .releases
+                .get(i + 1)
+                .map_or(usize::MAX, |next| next.heading_span.start);
+            if lists
+                .iter()
+                .any(|s| release.heading_span.end <= s.start && s.start < end)
+            {
+                self.missing_change_type.insert(release.heading_span);
+            }
+        }
+    }
+
     fn visit_release(&mut self, context: &mut Context, release: &parsed::ParsedRelease) {
-        if release.changes.is_empty() {
-            context.report(self.rule(), Some(release.heading_span));
+        if release.changes.is_empty() && !self.missing_change_type.contains(&release.heading_span) {
+            context.report(self.rule(), Some(release.version.span));
         }
     }
 
     fn visit_changes(&mut self, context: &mut Context, changes: &parsed::ParsedChanges) {
         if changes.items.is_empty() {
-            context.report(self.rule(), Some(changes.heading_span));
+            context.report(self.rule(), Some(changes.kind.span));
         }
     }
 }
@@ -41,10 +79,7 @@ impl Check for UnknownChangeType {
     }
 
     fn visit_changes(&mut self, context: &mut Context, changes: &parsed::ParsedChanges) {
-        if !matches!(
-            changes.kind.value,
-            "Added" | "Changed" | "Deprecated" | "Fixed" | "Removed" | "Security"
-        ) {
+        if !CHANGE_TYPES.contains(&changes.kind.value) {
             context.report(self.rule(), Some(changes.kind.span));
         }
     }
@@ -75,6 +110,42 @@ impl Check for DuplicateChangeType {
     }
 }
 
+invalid_span!(UnexpectedContent);
+
+#[derive(Default)]
+pub struct MissingChangeType {
+    marker: String,
+}
+
+impl Check for MissingChangeType {
+    fn rule(&self) -> Rule {
+        Rule::MissingChangeType
+    }
+
+    fn visit_changelog(&mut self, _context: &mut Context, changelog: &parsed::ParsedChangelog) {
+        self.marker = "#".repeat(changelog.level + 2);
+    }
+
+    fn visit_invalid_span(&mut self, context: &mut Context, span: &parsed::InvalidSpan) {
+        if let parsed::InvalidSpan::MissingChangeType(s) = span {
+            // Offer to wrap the list in each of the known change types.
+            let fixes = CHANGE_TYPES
+                .iter()
+                .map(|kind| {
+                    Fix::new(
+                        format!("Add a `{} {}` heading", self.marker, kind),
+                        vec![Edit::insert(
+                            s.start,
+                            format!("{} {}\n\n", self.marker, kind),
+                        )],
+                    )
+                })
+                .collect();
+            context.report_diagnostic(Diagnostic::new(self.rule(), Some(*s)).with_fixes(fixes));
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -144,7 +215,7 @@ mod tests {
                     },
                     // Empty changes.
                     ParsedChanges {
-                        heading_span: Span::new(1, usize::MAX),
+                        kind: Spanned::new(Span::new(1, usize::MAX), "Changed"),
                         ..Default::default()
                     },
                 ],
@@ -157,7 +228,7 @@ mod tests {
         // ParsedRelease with no changes.
         let changelog = ParsedChangelog {
             releases: vec![ParsedRelease {
-                heading_span: Span::new(1, usize::MAX),
+                version: Spanned::new(Span::new(1, usize::MAX), "1.0.0"),
                 ..Default::default()
             }],
             ..Default::default()
@@ -175,7 +246,7 @@ mod tests {
                     },
                     // Empty changes.
                     ParsedChanges {
-                        heading_span: Span::new(1, usize::MAX),
+                        kind: Spanned::new(Span::new(1, usize::MAX), "Changed"),
                         ..Default::default()
                     },
                 ],
@@ -265,4 +336,47 @@ mod tests {
         };
         assert_yaml_snapshot!(linter.lint(&changelog));
     }
+
+    #[test]
+    fn test_unexpected_content() {
+        let ruleset = RuleSet::from([Rule::UnexpectedContent]);
+        let linter = Linter::new(&ruleset);
+
+        let changelog = ParsedChangelog::default();
+        assert_yaml_snapshot!(linter.lint(&changelog));
+
+        let changelog = ParsedChangelog {
+            invalid_spans: vec![InvalidSpan::UnexpectedContent(Span::new(1, usize::MAX))],
+            ..Default::default()
+        };
+        assert_yaml_snapshot!(linter.lint(&changelog));
+    }
+
+    #[test]
+    fn test_missing_change_type() {
+        let ruleset = RuleSet::from([Rule::MissingChangeType]);
+        let linter = Linter::new(&ruleset);
+
+        let changelog = ParsedChangelog::default();
+        assert_yaml_snapshot!(linter.lint(&changelog));
+
+        let changelog = ParsedChangelog {
+            invalid_spans: vec![InvalidSpan::MissingChangeType(Span::new(1, usize::MAX))],
+            ..Default::default()
+        };
+        assert_yaml_snapshot!(linter.lint(&changelog));
+    }
+
+    #[test]
+    fn test_empty_section_missing_change_type() {
+        let ruleset = RuleSet::from([Rule::EmptySection, Rule::MissingChangeType]);
+        let linter = Linter::new(&ruleset);
+        let changelog = crate::parse(
+            "# Changelog\n\n## [1.0.0] - 2025-01-01\n\n* Add foo\n\n## [0.1.0] - 2024-01-01\n\n[1.0.0]: https://example.org/\n[0.1.0]: https://example.org/\n",
+        );
+        let diagnostics = linter.lint(&changelog);
+        let codes: Vec<&str> = diagnostics.iter().map(|d| d.code()).collect();
+        // The release with a list is missing a change type; the release without one is empty.
+        assert_eq!(codes, vec!["E106", "E102"]);
+    }
 }
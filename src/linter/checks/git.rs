@@ -0,0 +1,462 @@
+//! `E400` Git
+use std::collections::HashSet;
+
+use crate::date::Date;
+use crate::diagnostic::Diagnostic;
+
+use super::preamble::*;
+
+#[derive(Default)]
+pub struct MissingTag;
+
+impl Check for MissingTag {
+    fn rule(&self) -> Rule {
+        Rule::MissingTag
+    }
+
+    fn visit_release(&mut self, context: &mut Context, release: &parsed::ParsedRelease) {
+        if let Some(tags) = context.tags
+            && !tags.iter().any(|tag| tag.version == release.version.value)
+        {
+            context.report(self.rule(), Some(release.version.span));
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct MissingRelease {
+    versions: HashSet<String>,
+}
+
+impl Check for MissingRelease {
+    fn rule(&self) -> Rule {
+        Rule::MissingRelease
+    }
+
+    fn visit_release(&mut self, _context: &mut Context, release: &parsed::ParsedRelease) {
+        self.versions.insert(release.version.value.to_string());
+    }
+
+    fn finalize(&mut self, context: &mut Context) {
+        let Some(tags) = context.tags else {
+            return;
+        };
+        let diagnostics: Vec<Diagnostic> = tags
+            .iter()
+            .filter(|tag| !self.versions.contains(&tag.version))
+            .map(|tag| Diagnostic::new(self.rule(), None).with_argument(&tag.name))
+            .collect();
+        for diagnostic in diagnostics {
+            context.report_diagnostic(diagnostic);
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct TagDateMismatch;
+
+impl Check for TagDateMismatch {
+    fn rule(&self) -> Rule {
+        Rule::TagDateMismatch
+    }
+
+    fn visit_release(&mut self, context: &mut Context, release: &parsed::ParsedRelease) {
+        let Some(tags) = context.tags else {
+            return;
+        };
+        let Some(date) = release.date.and_then(|d| d.value.parse::<Date>().ok()) else {
+            // Other rules report missing or invalid dates.
+            return;
+        };
+        let tag_date = tags
+            .iter()
+            .find(|tag| tag.version == release.version.value)
+            .and_then(|tag| tag.date);
+        if let Some(tag_date) = tag_date
+            && date.days().abs_diff(tag_date.days()) > context.date_tolerance as u64
+        {
+            context.report(self.rule(), release.date.map(|d| d.span));
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct MissingUnreleasedChanges;
+
+impl Check for MissingUnreleasedChanges {
+    fn rule(&self) -> Rule {
+        Rule::MissingUnreleasedChanges
+    }
+
+    fn visit_changelog(&mut self, context: &mut Context, changelog: &parsed::ParsedChangelog) {
+        let Some(commits) = context.unreleased_commits else {
+            return;
+        };
+        let has_changes = changelog
+            .unreleased
+            .as_ref()
+            .is_some_and(|u| u.changes.iter().any(|c| !c.items.is_empty()));
+        if commits.count > 0 && !has_changes {
+            let span = changelog.unreleased.as_ref().map(|u| u.heading_span);
+            context.report_diagnostic(
+                Diagnostic::new(self.rule(), span).with_argument(&commits.since),
+            );
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct ModifiedRelease;
+
+impl Check for ModifiedRelease {
+    fn rule(&self) -> Rule {
+        Rule::ModifiedRelease
+    }
+
+    fn visit_changelog(&mut self, context: &mut Context, changelog: &parsed::ParsedChangelog) {
+        let Some(changes) = context.changes else {
+            return;
+        };
+        let mut diagnostics = Vec::new();
+        for release in &changelog.releases {
+            // A new release, e.g. the unreleased section becoming a release, may change freely.
+            if context
+                .released_versions
+                .is_some_and(|versions| !versions.iter().any(|v| v == release.version.value))
+            {
+                continue;
+            }
+            let span = Span::new(
+                release.heading_span.start,
+                section_end(changelog.source, release),
+            );
+            // Lines deleted just before the heading belong to the previous section, e.g. a
+            // removed release.
+            let in_release = |change: &&Span| {
+                change.overlaps(&span) && *change != &Span::new(span.start, span.start)
+            };
+            if let Some(change) = changes.iter().find(in_release) {
+                diagnostics.push(
+                    Diagnostic::new(self.rule(), Some(*change))
+                        .with_argument(release.version.value),
+                );
+            }
+        }
+        for diagnostic in diagnostics {
+            context.report_diagnostic(diagnostic);
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct RemovedRelease;
+
+impl Check for RemovedRelease {
+    fn rule(&self) -> Rule {
+        Rule::RemovedRelease
+    }
+
+    fn visit_changelog(&mut self, context: &mut Context, changelog: &parsed::ParsedChangelog) {
+        let (Some(changes), Some(versions)) = (context.changes, context.released_versions) else {
+            return;
+        };
+        let release = |version: &str| {
+            changelog
+                .releases
+                .iter()
+                .find(|r| r.version.value == version)
+        };
+        let mut diagnostics = Vec::new();
+        for (i, version) in versions.iter().enumerate() {
+            if release(version).is_some() {
+                continue;
+            }
+            // The deletion is at the start of the release that followed it, if it still exists.
+            let location = versions[i + 1..]
+                .iter()
+                .find_map(|v| release(v))
+                .map(|r| Span::new(r.heading_span.start, r.heading_span.start))
+                .filter(|deleted| changes.contains(deleted));
+            diagnostics.push(Diagnostic::new(self.rule(), location).with_argument(version));
+        }
+        for diagnostic in diagnostics {
+            context.report_diagnostic(diagnostic);
+        }
+    }
+}
+
+/// Return the end of the last line of a release section, including the line ending.
+fn section_end(source: &str, release: &parsed::ParsedRelease) -> usize {
+    let end = release
+        .changes
+        .iter()
+        .flat_map(|c| std::iter::once(c.heading_span).chain(c.items.iter().map(|i| i.span)))
+        .map(|span| span.end)
+        .fold(release.heading_span.end, usize::max);
+    match source.get(end..).and_then(|s| s.find('\n')) {
+        Some(i) => end + i + 1,
+        None => end,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use insta::assert_yaml_snapshot;
+
+    use crate::changelog::parsed::{
+        ParsedChangelog, ParsedChanges, ParsedRelease, ParsedUnreleased,
+    };
+    use crate::linter::Linter;
+    use crate::parser::parse;
+    use crate::ruleset::RuleSet;
+    use crate::span::{Span, Spanned};
+    use crate::vcs::{Commits, Tag};
+
+    fn changelog() -> ParsedChangelog<'static> {
+        ParsedChangelog {
+            releases: vec![
+                ParsedRelease {
+                    version: Spanned::new(Span::new(1, usize::MAX), "1.1.0"),
+                    date: Some(Spanned::new(Span::new(2, usize::MAX), "2025-03-01")),
+                    ..Default::default()
+                },
+                ParsedRelease {
+                    version: Spanned::new(Span::new(3, usize::MAX), "1.0.0"),
+                    date: Some(Spanned::new(Span::new(4, usize::MAX), "2025-01-01")),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        }
+    }
+
+    fn tags() -> Vec<Tag> {
+        vec![
+            Tag::new("v1.0.0", "1.0.0", Date::new(2025, 1, 8)),
+            Tag::new("v0.1.0", "0.1.0", Date::new(2024, 1, 1)),
+        ]
+    }
+
+    #[test]
+    fn test_missing_tag() {
+        let ruleset = RuleSet::from([Rule::MissingTag]);
+        let linter = Linter::new(&ruleset);
+        assert_eq!(linter.lint(&changelog()), vec![]);
+
+        let linter = linter.with_tags(Some(tags()));
+        assert_yaml_snapshot!(linter.lint(&changelog()));
+    }
+
+    #[test]
+    fn test_missing_release() {
+        let ruleset = RuleSet::from([Rule::MissingRelease]);
+        let linter = Linter::new(&ruleset);
+        assert_eq!(linter.lint(&changelog()), vec![]);
+
+        let linter = linter.with_tags(Some(tags()));
+        assert_yaml_snapshot!(linter.lint(&changelog()));
+    }
+
+    #[test]
+    fn test_tag_date_mismatch() {
+        let ruleset = RuleSet::from([Rule::TagDateMismatch]);
+        let linter = Linter::new(&ruleset);
+        assert_eq!(linter.lint(&changelog()), vec![]);
+
+        let linter = linter.with_tags(Some(tags()));
+        assert_yaml_snapshot!(linter.lint(&changelog()));
+
+        let linter = linter.with_date_tolerance(7);
+        assert_eq!(linter.lint(&changelog()), vec![]);
+    }
+
+    #[test]
+    fn test_missing_unreleased_changes() {
+        let ruleset = RuleSet::from([Rule::MissingUnreleasedChanges]);
+        let linter = Linter::new(&ruleset);
+        let mut changelog = ParsedChangelog {
+            unreleased: Some(ParsedUnreleased {
+                heading_span: Span::new(1, usize::MAX),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        assert_eq!(linter.lint(&changelog), vec![]);
+
+        let linter = linter.with_unreleased_commits(Some(Commits::new("v1.0.0", 0)));
+        assert_eq!(linter.lint(&changelog), vec![]);
+
+        let linter = linter.with_unreleased_commits(Some(Commits::new("v1.0.0", 2)));
+        assert_yaml_snapshot!(linter.lint(&changelog));
+
+        changelog.unreleased = Some(ParsedUnreleased {
+            changes: vec![ParsedChanges {
+                kind: Spanned::new(Span::default(), "Added"),
+                items: vec![Spanned::new(Span::default(), "Add foo")],
+                ..Default::default()
+            }],
+            ..Default::default()
+        });
+        assert_eq!(linter.lint(&changelog), vec![]);
+    }
+
+    #[test]
+    fn test_modified_release() {
+        let ruleset = RuleSet::from([Rule::ModifiedRelease]);
+        let source = "# Changelog
+
+## [Unreleased]
+
+## [1.1.0] - 2025-03-01
+
+### Added
+
+* Foo
+
+## [1.0.0] - 2025-01-01
+
+### Added
+
+* Bar
+
+[unreleased]: https://example.org/
+[1.1.0]: https://example.org/
+[1.0.0]: https://example.org/
+";
+        let changelog = parse(source);
+        let linter = Linter::new(&ruleset);
+        assert_eq!(linter.lint(&changelog), vec![]);
+
+        let line = |text: &str| {
+            let start = source.find(text).unwrap();
+            Span::new(start, start + text.len())
+        };
+        // Changes to the unreleased section.
+        let linter = Linter::new(&ruleset).with_changes(Some(vec![line("## [Unreleased]")]));
+        assert_eq!(linter.lint(&changelog), vec![]);
+        // A new release.
+        let released = Some(vec!["1.0.0".to_string()]);
+        let linter = Linter::new(&ruleset)
+            .with_changes(Some(vec![line("## [1.1.0] - 2025-03-01"), line("* Foo")]))
+            .with_released_versions(released.clone());
+        assert_eq!(linter.lint(&changelog), vec![]);
+        // Changes to the heading of a released section.
+        let linter = Linter::new(&ruleset)
+            .with_changes(Some(vec![line("## [1.0.0] - 2025-01-01"), line("* Bar")]))
+            .with_released_versions(released);
+        assert_eq!(
+            linter.lint(&changelog),
+            vec![
+                Diagnostic::new(Rule::ModifiedRelease, Some(line("## [1.0.0] - 2025-01-01")))
+                    .with_argument("1.0.0"),
+            ]
+        );
+        // Changes to released sections.
+        let deleted = line("* Bar").end + 1;
+        let deleted = Span::new(deleted, deleted);
+        let linter = Linter::new(&ruleset).with_changes(Some(vec![line("* Foo"), deleted]));
+        let diagnostics = linter.lint(&changelog);
+        assert_eq!(
+            diagnostics,
+            vec![
+                Diagnostic::new(Rule::ModifiedRelease, Some(line("* Foo"))).with_argument("1.1.0"),
+                Diagnostic::new(Rule::ModifiedRelease, Some(deleted)).with_argument("1.0.0"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_removed_release() {
+        let source = "# Changelog
+
+## [Unreleased]
+
+## [1.2.0] - 2025-05-01
+
+### Added
+
+* Foo
+
+## [1.0.0] - 2025-01-01
+
+### Added
+
+* Baz
+
+[unreleased]: https://example.org/
+[1.2.0]: https://example.org/
+[1.0.0]: https://example.org/
+";
+        let changelog = parse(source);
+        // `## [1.1.0]` and its section were deleted before `## [1.0.0]`.
+        let start = source.find("## [1.0.0]").unwrap();
+        let deleted = Span::new(start, start);
+        let released = Some(vec![
+            "1.2.0".to_string(),
+            "1.1.0".to_string(),
+            "1.0.0".to_string(),
+        ]);
+        let ruleset = RuleSet::from([Rule::ModifiedRelease, Rule::RemovedRelease]);
+        let linter = Linter::new(&ruleset)
+            .with_changes(Some(vec![deleted]))
+            .with_released_versions(released.clone());
+        assert_eq!(
+            linter.lint(&changelog),
+            vec![Diagnostic::new(Rule::RemovedRelease, Some(deleted)).with_argument("1.1.0")]
+        );
+        // Without a diff, there are no removed releases.
+        let linter = Linter::new(&ruleset).with_released_versions(released);
+        assert_eq!(linter.lint(&changelog), vec![]);
+    }
+
+    #[test]
+    fn test_changes() {
+        let ruleset = RuleSet::default();
+        let source = "# Changelog
+
+## [Unreleased]
+
+## [1.1.0] - 2025-02-30
+
+### Added
+
+* Foo
+
+## [1.0.0] - 2025-01-01
+
+### Fixed
+
+### Added
+
+[unreleased]: https://example.org/
+[1.1.0]: https://example.org/
+[1.0.0]: https://example.org/
+";
+        let changelog = parse(source);
+        let linter = Linter::new(&ruleset);
+        assert_eq!(
+            linter
+                .lint(&changelog)
+                .iter()
+                .map(|d| d.rule)
+                .collect::<Vec<_>>(),
+            vec![
+                Rule::InvalidDateFormat,
+                Rule::EmptySection,
+                Rule::EmptySection
+            ]
+        );
+
+        let start = source.find("## [1.1.0]").unwrap();
+        let linter = linter
+            .with_changes(Some(vec![Span::new(start, start + 23)]))
+            .with_released_versions(Some(vec!["1.0.0".to_string()]));
+        let diagnostics = linter.lint(&changelog);
+        assert_eq!(
+            diagnostics.iter().map(|d| d.rule).collect::<Vec<_>>(),
+            vec![Rule::InvalidDateFormat]
+        );
+    }
+}
@@ -6,6 +6,7 @@ use version_compare::{Cmp, Version};
 use super::preamble::*;
 
 use crate::changelog::parsed;
+use crate::date::Date;
 
 #[derive(Default)]
 pub struct InvalidDate;
@@ -16,10 +17,29 @@ impl Check for InvalidDate {
     }
 
     fn visit_release(&mut self, context: &mut Context, release: &parsed::ParsedRelease) {
-        if let Some(spanned) = &release.date {
-            if !is_iso8601_date(spanned.value) {
-                context.report(self.rule(), Some(spanned.span));
-            }
+        if let Some(spanned) = &release.date
+            && spanned.value.parse::<Date>().is_err()
+        {
+            context.report(self.rule(), Some(spanned.span));
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct FutureDate;
+
+impl Check for FutureDate {
+    fn rule(&self) -> Rule {
+        Rule::FutureDate
+    }
+
+    fn visit_release(&mut self, context: &mut Context, release: &parsed::ParsedRelease) {
+        if let Some(spanned) = &release.date
+            && let Ok(date) = spanned.value.parse::<Date>()
+            // Allow a day for releases dated in a time zone ahead of UTC.
+            && date.days() > context.today.days() + 1
+        {
+            context.report(self.rule(), Some(spanned.span));
         }
     }
 }
@@ -33,10 +53,10 @@ impl Check for InvalidYanked {
     }
 
     fn visit_release(&mut self, context: &mut Context, release: &parsed::ParsedRelease) {
-        if let Some(spanned) = &release.yanked {
-            if spanned.value != "[YANKED]" {
-                context.report(self.rule(), Some(spanned.span));
-            }
+        if let Some(spanned) = &release.yanked
+            && spanned.value != "[YANKED]"
+        {
+            context.report(self.rule(), Some(spanned.span));
         }
     }
 }
@@ -128,18 +148,6 @@ impl Check for DuplicateVersion {
     }
 }
 
-fn is_iso8601_date(s: &str) -> bool {
-    if s.len() != 10 {
-        return false;
-    }
-    let bytes = s.as_bytes();
-    bytes[0..4].iter().all(|u| u.is_ascii_digit())
-        && bytes[4] == b'-'
-        && bytes[5..7].iter().all(|u| u.is_ascii_digit())
-        && bytes[7] == b'-'
-        && bytes[8..9].iter().all(|u| u.is_ascii_digit())
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -172,6 +180,54 @@ mod tests {
                     date: Some(Spanned::new(Span::new(1, usize::MAX), "foo")),
                     ..Default::default()
                 },
+                ParsedRelease {
+                    date: Some(Spanned::new(Span::new(2, usize::MAX), "2025-13-45")),
+                    ..Default::default()
+                },
+                ParsedRelease {
+                    date: Some(Spanned::new(Span::new(3, usize::MAX), "2025-02-3x")),
+                    ..Default::default()
+                },
+                ParsedRelease {
+                    date: Some(Spanned::new(Span::new(4, usize::MAX), "2025-02-29")),
+                    ..Default::default()
+                },
+                ParsedRelease {
+                    date: Some(Spanned::new(Span::new(0, 9), "2024-02-29")),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+        assert_yaml_snapshot!(linter.lint(&changelog));
+    }
+
+    #[test]
+    fn test_future_date() {
+        let ruleset = RuleSet::from([Rule::FutureDate]);
+        let linter = Linter::new(&ruleset).with_today(Date::new(2025, 6, 1));
+
+        let changelog = ParsedChangelog::default();
+        assert_yaml_snapshot!(linter.lint(&changelog));
+
+        let changelog = ParsedChangelog {
+            releases: vec![
+                ParsedRelease {
+                    date: Some(Spanned::new(Span::new(1, usize::MAX), "2025-06-03")),
+                    ..Default::default()
+                },
+                ParsedRelease {
+                    date: Some(Spanned::new(Span::new(2, 12), "2025-06-02")),
+                    ..Default::default()
+                },
+                ParsedRelease {
+                    date: Some(Spanned::new(Span::new(0, 9), "2025-06-01")),
+                    ..Default::default()
+                },
+                ParsedRelease {
+                    date: Some(Spanned::new(Span::new(0, 9), "9999-99-99")),
+                    ..Default::default()
+                },
             ],
             ..Default::default()
         };
@@ -7,6 +7,11 @@ use notabene::cli;
 fn main() -> std::process::ExitCode {
     match cli::main() {
         Ok(_) => ExitCode::SUCCESS,
-        Err(_) => ExitCode::FAILURE,
+        Err(err) => {
+            if !err.is_check() {
+                eprintln!("error: {}", err);
+            }
+            err.exit_code()
+        }
     }
 }
@@ -9,6 +9,10 @@ use std::iter::Peekable;
 use std::rc::Rc;
 
 use pulldown_cmark as md;
+#[cfg(feature = "serde")]
+use serde::de::{self, Visitor};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 enum Section<'a> {
     Unreleased(ParsedUnreleased<'a>),
@@ -16,20 +20,265 @@ enum Section<'a> {
     Invalid(InvalidSpan),
 }
 
-/// Parse a changelog.
+type Blocks = Peekable<std::vec::IntoIter<Block>>;
+
+/// The heading levels of a changelog.
 ///
-/// This function is infallible. It will return a `ParsedChangelog` even if the document is
-/// completely malformed. `ParsedChangelog captures structural and semantic errors for the linter.
+/// By default, the title is an `h1`, releases are `h2`, and change types are `h3`. Changelogs
+/// embedded in other documents often use deeper headings, e.g. `## Changelog`, `### [1.0.0]`, and
+/// `#### Added`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Layout {
+    /// Use this heading level for the title. Releases and change types are one and two levels
+    /// deeper, respectively. Valid levels are 1 to 4.
+    Level(usize),
+    /// Detect the heading levels from the first release heading.
+    Auto,
+}
+
+impl Default for Layout {
+    fn default() -> Self {
+        Self::Level(1)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for Layout {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            Self::Level(level) => serializer.serialize_u64(*level as u64),
+            Self::Auto => serializer.serialize_str("auto"),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for Layout {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct LayoutVisitor;
+
+        impl<'de> Visitor<'de> for LayoutVisitor {
+            type Value = Layout;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("a heading level from 1 to 4 or \"auto\"")
+            }
+
+            fn visit_i64<E>(self, value: i64) -> Result<Layout, E>
+            where
+                E: de::Error,
+            {
+                match value {
+                    1..=4 => Ok(Layout::Level(value as usize)),
+                    _ => Err(de::Error::invalid_value(
+                        de::Unexpected::Signed(value),
+                        &self,
+                    )),
+                }
+            }
+
+            fn visit_u64<E>(self, value: u64) -> Result<Layout, E>
+            where
+                E: de::Error,
+            {
+                match value {
+                    1..=4 => Ok(Layout::Level(value as usize)),
+                    _ => Err(de::Error::invalid_value(
+                        de::Unexpected::Unsigned(value),
+                        &self,
+                    )),
+                }
+            }
+
+            fn visit_str<E>(self, value: &str) -> Result<Layout, E>
+            where
+                E: de::Error,
+            {
+                match value {
+                    "auto" => Ok(Layout::Auto),
+                    _ => Err(de::Error::invalid_value(de::Unexpected::Str(value), &self)),
+                }
+            }
+        }
+
+        deserializer.deserialize_any(LayoutVisitor)
+    }
+}
+
+/// The part of a document that contains the changelog.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub enum Region {
+    /// The whole document.
+    #[default]
+    Document,
+    /// The section under the heading with this anchor or text, e.g. `changelog` for
+    /// `## Changelog`. The section includes the heading itself.
+    Section(String),
+    /// The content between the `<!-- changelog:start -->` and `<!-- changelog:end -->` markers.
+    Markers,
+}
+
+impl Region {
+    /// The marker that starts a [`Region::Markers`] region.
+    pub const START_MARKER: &str = "<!-- changelog:start -->";
+    /// The marker that ends a [`Region::Markers`] region.
+    pub const END_MARKER: &str = "<!-- changelog:end -->";
+
+    /// Find the region in the document.
+    ///
+    /// Return `None` if the document does not contain the section or markers.
+    pub fn find(&self, s: &str) -> Option<Span> {
+        let (blocks, _) = parse_blocks(s);
+        self.find_in(s, &blocks)
+    }
+
+    fn find_in(&self, s: &str, blocks: &[Block]) -> Option<Span> {
+        match self {
+            Self::Document => Some(Span::new(0, s.len())),
+            Self::Section(anchor) => {
+                let (i, heading) =
+                    blocks
+                        .iter()
+                        .enumerate()
+                        .find_map(|(i, block)| match block {
+                            Block::Heading(heading) if heading_matches(s, heading, anchor) => {
+                                Some((i, heading))
+                            }
+                            _ => None,
+                        })?;
+                // The section ends at the next heading of the same or higher level.
+                let end = blocks[i + 1..]
+                    .iter()
+                    .find_map(|block| match block {
+                        Block::Heading(h) if h.level <= heading.level => Some(h.span.start),
+                        _ => None,
+                    })
+                    .unwrap_or(s.len());
+                Some(Span::new(heading.span.start, end))
+            }
+            Self::Markers => {
+                let start = find_line(s, 0, Self::START_MARKER)?.end;
+                let end = find_line(s, start, Self::END_MARKER)
+                    .map(|span| span.start)
+                    .unwrap_or(s.len());
+                Some(Span::new(start, end))
+            }
+        }
+    }
+}
+
+/// A changelog parser.
 ///
-/// This function returns a borrowed version of the changelog.
-/// To convert the result into an owned version, use [`ParsedChangelog::to_owned`].
+/// Use [`parse`] to parse a changelog with the default options.
 ///
-/// Use [`Linter`](crate::Linter) to report structural and semantic diagnostics.
-pub fn parse<'a>(s: &'a str) -> ParsedChangelog<'a> {
-    let mut changelog = ParsedChangelog {
-        source: s,
-        ..Default::default()
-    };
+/// ```rust
+/// use notabene::{Layout, Parser, Region};
+/// let s = "# README\n\n## Changelog\n\n### [Unreleased]\n";
+/// let changelog = Parser::new()
+///     .with_layout(Layout::Auto)
+///     .with_region(Region::Section("changelog".to_string()))
+///     .parse(s);
+/// ```
+#[derive(Debug, Default)]
+pub struct Parser {
+    layout: Layout,
+    region: Region,
+}
+
+impl Parser {
+    /// Create a new parser with the default options.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the heading levels of the changelog.
+    pub fn with_layout(self, layout: Layout) -> Self {
+        Self { layout, ..self }
+    }
+
+    /// Set the part of the document that contains the changelog.
+    ///
+    /// Spans in the parsed changelog are relative to the whole document. If the document does not
+    /// contain the region, the changelog is empty.
+    pub fn with_region(self, region: Region) -> Self {
+        Self { region, ..self }
+    }
+
+    /// Parse a changelog.
+    ///
+    /// See [`parse`] for details.
+    pub fn parse<'a>(&self, s: &'a str) -> ParsedChangelog<'a> {
+        let mut changelog = ParsedChangelog {
+            source: s,
+            ..Default::default()
+        };
+        let (mut blocks, mut broken_links) = parse_blocks(s);
+        let region = self
+            .region
+            .find_in(s, &blocks)
+            .unwrap_or(Span::new(s.len(), s.len()));
+        let contains = |span: Span| region.start <= span.start && span.end <= region.end;
+        blocks.retain(|block| contains(block.span()));
+        broken_links.retain(|link| match link {
+            InvalidSpan::UndefinedLinkReference(span) => contains(*span),
+            _ => true,
+        });
+        let level = match self.layout {
+            Layout::Level(level) => level,
+            Layout::Auto => detect_level(&blocks),
+        };
+        changelog.level = level;
+        let mut blocks = blocks.into_iter().peekable();
+        while let Some(block) = blocks.next() {
+            match block {
+                Block::Heading(heading) if heading.level == level => {
+                    match get_heading_span(&heading) {
+                        Some(span) => match changelog.title {
+                            Some(_) => changelog
+                                .invalid_spans
+                                .push(InvalidSpan::DuplicateTitle(span)),
+                            None => changelog.title = Some(Spanned::new(span, &s[span.range()])),
+                        },
+                        None => changelog
+                            .invalid_spans
+                            .push(InvalidSpan::InvalidTitle(heading.span)),
+                    };
+                }
+                Block::Heading(heading) if heading.level == level + 1 => {
+                    let section = parse_section(
+                        s,
+                        &heading,
+                        &mut blocks,
+                        level,
+                        &mut changelog.invalid_spans,
+                    );
+                    match section {
+                        Section::Unreleased(u) => match changelog.unreleased {
+                            Some(_) => changelog
+                                .invalid_spans
+                                .push(InvalidSpan::DuplicateUnreleased(u.heading_span)),
+                            None => changelog.unreleased = Some(u),
+                        },
+                        Section::Release(r) => changelog.releases.push(r),
+                        Section::Invalid(i) => changelog.invalid_spans.push(i),
+                    }
+                }
+                _ => {}
+            }
+        }
+        changelog.invalid_spans.append(&mut broken_links);
+        changelog
+    }
+}
+
+/// Parse the document as a sequence of blocks and collect any broken links.
+fn parse_blocks(s: &str) -> (Vec<Block>, Vec<InvalidSpan>) {
     let broken_links = Rc::new(RefCell::new(Vec::new()));
     let callback = {
         let broken_links = Rc::clone(&broken_links);
@@ -42,57 +291,97 @@ pub fn parse<'a>(s: &'a str) -> ParsedChangelog<'a> {
     };
     let parser = md::Parser::new_with_broken_link_callback(s, md::Options::empty(), Some(callback));
     let parser = md::utils::TextMergeWithOffset::new(parser.into_offset_iter());
-    let mut blocks = ast::Parser::new(Box::new(parser)).peekable();
-    while let Some(block) = blocks.next() {
-        match block {
-            Block::Heading(heading @ Heading { level: 1, .. }) => {
-                match get_heading_span(&heading) {
-                    Some(span) => match changelog.title {
-                        Some(_) => changelog
-                            .invalid_spans
-                            .push(InvalidSpan::DuplicateTitle(span)),
-                        None => changelog.title = Some(Spanned::new(span, &s[span.range()])),
-                    },
-                    None => changelog
-                        .invalid_spans
-                        .push(InvalidSpan::InvalidTitle(heading.span)),
-                };
-            }
-            Block::Heading(heading @ Heading { level: 2, .. }) => {
-                let section = parse_section(s, &heading, &mut blocks);
-                match section {
-                    Section::Unreleased(u) => match changelog.unreleased {
-                        Some(_) => changelog
-                            .invalid_spans
-                            .push(InvalidSpan::DuplicateUnreleased(u.heading_span)),
-                        None => changelog.unreleased = Some(u),
-                    },
-                    Section::Release(r) => changelog.releases.push(r),
-                    Section::Invalid(i) => changelog.invalid_spans.push(i),
-                }
-            }
-            _ => {}
+    // Consuming the parser also drops its reference to `callback`.
+    let blocks: Vec<Block> = ast::Parser::new(Box::new(parser)).collect();
+    let broken_links = Rc::try_unwrap(broken_links)
+        .map(|cell| cell.into_inner())
+        .unwrap_or_default();
+    (blocks, broken_links)
+}
+
+/// Find the first line after `start` that contains only `text`.
+fn find_line(s: &str, start: usize, text: &str) -> Option<Span> {
+    let mut offset = start;
+    for line in s[start..].split_inclusive('\n') {
+        if line.trim() == text {
+            return Some(Span::new(offset, offset + line.len()));
         }
+        offset += line.len();
     }
-    // `blocks` still holds a reference to `callback` through the parser.
-    drop(blocks);
-    if let Ok(cell) = Rc::try_unwrap(broken_links) {
-        changelog.invalid_spans.append(&mut cell.into_inner());
-    }
-    changelog
+    None
+}
+
+/// Return whether the heading text or its anchor matches `anchor`.
+fn heading_matches(s: &str, heading: &Heading, anchor: &str) -> bool {
+    let text: String = heading
+        .inlines
+        .iter()
+        .map(|inline| match inline {
+            Inline::Link(l) => &s[l.content.span.range()],
+            Inline::Literal(l) => &s[l.span.range()],
+        })
+        .collect();
+    let anchor = anchor.trim_start_matches('#');
+    text.trim().eq_ignore_ascii_case(anchor) || slugify(&text) == anchor.to_lowercase()
+}
+
+/// Return the GitHub-style anchor for heading text.
+fn slugify(text: &str) -> String {
+    text.trim()
+        .to_lowercase()
+        .chars()
+        .filter_map(|c| match c {
+            ' ' => Some('-'),
+            '-' | '_' => Some(c),
+            c if c.is_alphanumeric() => Some(c),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Parse a changelog.
+///
+/// This function is infallible. It will return a `ParsedChangelog` even if the document is
+/// completely malformed. `ParsedChangelog captures structural and semantic errors for the linter.
+///
+/// This function returns a borrowed version of the changelog.
+/// To convert the result into an owned version, use [`ParsedChangelog::to_owned`].
+///
+/// Use [`Linter`](crate::Linter) to report structural and semantic diagnostics.
+///
+/// Use [`Parser`] to customize the parser.
+pub fn parse<'a>(s: &'a str) -> ParsedChangelog<'a> {
+    Parser::default().parse(s)
+}
+
+/// Return the title heading level implied by the first release heading.
+fn detect_level(blocks: &[Block]) -> usize {
+    blocks
+        .iter()
+        .find_map(|block| match block {
+            Block::Heading(heading @ Heading { level: 2.., .. }) => match heading.inlines.first() {
+                Some(Inline::Link(_)) => Some(heading.level - 1),
+                _ => None,
+            },
+            _ => None,
+        })
+        .unwrap_or(1)
 }
 
 fn parse_section<'a>(
     s: &'a str,
     heading: &Heading,
-    blocks: &mut Peekable<ast::Parser<'a>>,
+    blocks: &mut Blocks,
+    level: usize,
+    invalid_spans: &mut Vec<InvalidSpan>,
 ) -> Section<'a> {
     match heading.inlines.as_slice() {
         // Unreleased
         [Inline::Link(l)] if &s[l.content.span.range()] == "Unreleased" => {
-            let changes = parse_changes(s, blocks);
+            let changes = parse_changes(s, blocks, level, invalid_spans);
             Section::Unreleased(ParsedUnreleased {
                 heading_span: heading.span,
+                link_span: l.span,
                 url: Some(l.target.clone()),
                 changes,
             })
@@ -101,15 +390,18 @@ fn parse_section<'a>(
         [Inline::Link(l), Inline::Literal(t)] => {
             let mut release = ParsedRelease {
                 heading_span: heading.span,
+                link_span: l.span,
                 version: Spanned::new(l.content.span, &s[l.content.span.range()]),
                 url: Some(l.target.clone()),
+                separator: None,
                 date: None,
                 yanked: None,
                 changes: vec![],
             };
             let mut spans = SpanIterator::new(&s[t.span.range()]);
-            // Skip hyphen.
-            spans.next();
+            if let Some(span) = spans.next().map(|s| s.offset(t.span.start)) {
+                release.separator = Some(Spanned::new(span, &s[span.range()]));
+            }
             if let Some(span) = spans.next().map(|s| s.offset(t.span.start)) {
                 let date = &s[span.range()];
                 release.date = Some(Spanned::new(span, date))
@@ -118,7 +410,7 @@ fn parse_section<'a>(
                 let yanked = &s[span.range()];
                 release.yanked = Some(Spanned::new(span, yanked));
             }
-            let changes = parse_changes(s, blocks);
+            let changes = parse_changes(s, blocks, level, invalid_spans);
             release.changes = changes;
             Section::Release(release)
         }
@@ -126,24 +418,31 @@ fn parse_section<'a>(
     }
 }
 
-fn parse_changes<'a>(s: &'a str, blocks: &mut Peekable<ast::Parser<'a>>) -> Vec<ParsedChanges<'a>> {
+fn parse_changes<'a>(
+    s: &'a str,
+    blocks: &mut Blocks,
+    level: usize,
+    invalid_spans: &mut Vec<InvalidSpan>,
+) -> Vec<ParsedChanges<'a>> {
     let mut sections: Vec<ParsedChanges> = Vec::new();
-    let mut current_kind: Option<&'a str> = None;
+    let mut current_kind: Option<Spanned<&'a str>> = None;
     let mut current_changes: Vec<Spanned<&'a str>> = Vec::new();
     let mut current_heading_span: Span = Span::default();
 
     while let Some(block) = blocks.peek() {
         match block {
-            Block::Heading(heading @ Heading { level: 3, .. }) => {
+            // A title or release heading ends the section.
+            Block::Heading(heading) if heading.level <= level + 1 => break,
+            Block::Heading(heading) if heading.level == level + 2 => {
                 if let Some(kind) = current_kind.take() {
-                    // TODO: more accurate span for kind
                     sections.push(ParsedChanges {
                         heading_span: current_heading_span,
-                        kind: Spanned::new(current_heading_span, kind),
+                        kind,
                         items: std::mem::take(&mut current_changes),
                     });
                 }
-                current_kind = get_heading_text(s, heading);
+                current_kind =
+                    get_heading_span(heading).map(|span| Spanned::new(span, &s[span.range()]));
                 current_heading_span = heading.span;
                 blocks.next();
             }
@@ -154,17 +453,26 @@ fn parse_changes<'a>(s: &'a str, blocks: &mut Peekable<ast::Parser<'a>>) -> Vec<
                             .iter()
                             .map(|i| Spanned::new(i.span, &s[i.span.range()])),
                     );
+                } else {
+                    invalid_spans.push(InvalidSpan::MissingChangeType(l.span));
                 }
                 blocks.next();
             }
-            _ => break,
+            // Anything else is content that does not belong in a release section, like release
+            // notes or a deeper heading. Report it instead of dropping it silently.
+            Block::Heading(Heading { span, .. })
+            | Block::Paragraph(Literal { span })
+            | Block::Other(Literal { span }) => {
+                invalid_spans.push(InvalidSpan::UnexpectedContent(*span));
+                blocks.next();
+            }
         }
     }
 
     if let Some(kind) = current_kind.take() {
         sections.push(ParsedChanges {
             heading_span: current_heading_span,
-            kind: Spanned::new(current_heading_span, kind),
+            kind,
             items: current_changes,
         });
     }
@@ -179,13 +487,6 @@ fn get_heading_span(heading: &Heading) -> Option<Span> {
     }
 }
 
-fn get_heading_text<'a>(s: &'a str, heading: &Heading) -> Option<&'a str> {
-    match heading.inlines.as_slice() {
-        [Inline::Literal(Literal { span, .. })] => Some(&s[span.range()]),
-        _ => None,
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -227,4 +528,196 @@ mod tests {
         let changelog = parse(source);
         assert_yaml_snapshot!(changelog);
     }
+
+    #[test]
+    fn test_parse_unexpected_content() {
+        let source = "# Changelog
+
+## [Unreleased]
+
+This release requires Rust 1.85.
+
+### Added
+
+* Add foo
+
+#### Notes
+
+```
+cargo install notabene
+```
+
+### Fixed
+
+* Fix bar
+
+[Unreleased]: https://example.org/
+";
+        let changelog = parse(source);
+        let spans: Vec<&str> = changelog
+            .invalid_spans
+            .iter()
+            .filter_map(|span| match span {
+                InvalidSpan::UnexpectedContent(span) => Some(source[span.range()].trim_end()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            spans,
+            vec![
+                "This release requires Rust 1.85.",
+                "#### Notes",
+                "```\ncargo install notabene\n```",
+            ]
+        );
+        // Content after unexpected blocks is still parsed.
+        let unreleased = changelog.unreleased.unwrap();
+        assert_eq!(unreleased.changes.len(), 2);
+        assert_eq!(unreleased.changes[1].kind.value, "Fixed");
+    }
+
+    #[test]
+    fn test_parse_missing_change_type() {
+        let source = "# Changelog
+
+## [1.0.0] - 2025-01-01
+
+* Add foo
+* Add bar
+
+### Fixed
+
+* Fix baz
+
+[1.0.0]: https://example.org/
+";
+        let changelog = parse(source);
+        assert_eq!(
+            changelog.invalid_spans,
+            vec![InvalidSpan::MissingChangeType(Span::new(38, 59))]
+        );
+        assert_eq!(changelog.releases[0].changes.len(), 1);
+    }
+
+    #[test]
+    fn test_parse_layout() {
+        let source = "# Project
+
+## Changelog
+
+### [Unreleased]
+
+#### Added
+
+* Add foo
+
+### [1.0.0] - 2025-01-01
+
+#### Fixed
+
+* Fix bar
+
+[Unreleased]: https://example.org/unreleased
+[1.0.0]: https://example.org/release/1.0.0
+";
+        let changelog = parse(source);
+        assert_eq!(changelog.title.map(|t| t.value), Some("Project"));
+        assert!(changelog.unreleased.is_none());
+        assert!(changelog.releases.is_empty());
+
+        for layout in [Layout::Level(2), Layout::Auto] {
+            let changelog = Parser::new().with_layout(layout).parse(source);
+            assert_eq!(changelog.level, 2);
+            assert_eq!(changelog.title.map(|t| t.value), Some("Changelog"));
+            let unreleased = changelog.unreleased.unwrap();
+            assert_eq!(unreleased.changes[0].kind.value, "Added");
+            assert_eq!(changelog.releases[0].version.value, "1.0.0");
+            assert_eq!(changelog.releases[0].changes[0].kind.value, "Fixed");
+            assert_eq!(changelog.invalid_spans, vec![]);
+        }
+
+        // Fall back to the default level without any releases.
+        let changelog = Parser::new().with_layout(Layout::Auto).parse("# Changelog");
+        assert_eq!(changelog.level, 1);
+    }
+
+    #[test]
+    fn test_parse_region() {
+        let source = "# Project
+
+## Installation
+
+* Run `cargo install`
+
+## Release History
+
+### [1.0.0] - 2025-01-01
+
+#### Fixed
+
+* Fix [#1]
+
+## License
+
+MIT
+
+[1.0.0]: https://example.org/release/1.0.0
+";
+        for anchor in ["release-history", "#release-history", "Release History"] {
+            let changelog = Parser::new()
+                .with_layout(Layout::Auto)
+                .with_region(Region::Section(anchor.to_string()))
+                .parse(source);
+            assert_eq!(changelog.title.map(|t| t.value), Some("Release History"));
+            assert_eq!(changelog.releases.len(), 1);
+            assert_eq!(changelog.releases[0].changes[0].kind.value, "Fixed");
+            // Spans are relative to the whole document.
+            assert_eq!(&source[changelog.releases[0].version.span.range()], "1.0.0");
+            // Only broken links within the section are reported.
+            match changelog.invalid_spans.as_slice() {
+                [InvalidSpan::UndefinedLinkReference(span)] => {
+                    assert_eq!(&source[span.range()], "[#1]")
+                }
+                spans => panic!("unexpected invalid spans: {:?}", spans),
+            }
+        }
+
+        let changelog = Parser::new()
+            .with_region(Region::Section("changes".to_string()))
+            .parse(source);
+        assert_eq!(changelog.title, None);
+        assert_eq!(changelog.invalid_spans, vec![]);
+    }
+
+    #[test]
+    fn test_parse_markers() {
+        let source = "# Project
+
+* Not a change
+
+<!-- changelog:start -->
+# Changelog
+
+## [1.0.0] - 2025-01-01
+
+### Fixed
+
+* Fix foo
+<!-- changelog:end -->
+
+## License
+
+[1.0.0]: https://example.org/release/1.0.0
+";
+        let changelog = Parser::new().with_region(Region::Markers).parse(source);
+        assert_eq!(changelog.title.map(|t| t.value), Some("Changelog"));
+        assert_eq!(changelog.releases.len(), 1);
+        assert_eq!(changelog.invalid_spans, vec![]);
+        assert_eq!(Region::Markers.find("# Changelog"), None);
+        // Markers must be on their own line.
+        assert_eq!(
+            Region::Markers.find("* Support `<!-- changelog:start -->`"),
+            None
+        );
+    }
 }
@@ -31,6 +31,7 @@ macro_rules! rules {
             ];
 
             /// All codes.
+            #[cfg(feature = "serde")]
             pub(crate) const CODES: [&str; [$(stringify!($code)),*].len()] = [
                 $($code),*
             ];
@@ -112,6 +113,16 @@ rules! {
         "E104",
         "Duplicate change type `{}`",
     ),
+    UnexpectedContent = (
+        "A section contains content other than change type headings and lists of changes (e.g. a paragraph or code block).",
+        "E105",
+        "Unexpected content in section",
+    ),
+    MissingChangeType = (
+        "A list of changes is not under a change type heading.",
+        "E106",
+        "Changes are missing a change type",
+    ),
     // E200 Release
     InvalidReleaseOrder = (
         "The release is not in reverse chronological order.",
@@ -129,7 +140,7 @@ rules! {
         "Release missing date",
     ),
     InvalidDateFormat = (
-        "The date is not in ISO 8601 format.",
+        "The date is not a valid calendar date in ISO 8601 format (`YYYY-MM-DD`).",
         "E203",
         "Invalid date `{}`",
     ),
@@ -138,12 +149,48 @@ rules! {
         "E204",
         "Invalid [YANKED] format `{}`",
     ),
-    // E500 Links
+    FutureDate = (
+        "The release date is more than one day in the future.",
+        "E205",
+        "Release date is in the future `{}`",
+    ),
+    // E300 Links
     UndefinedLinkReference = (
         "The target reference does not exist.",
         "E300",
         "Link reference does not exist: `{}`",
     ),
+    // E400 Git
+    MissingTag = (
+        "There is no git tag for this release.",
+        "E400",
+        "Release has no tag `{}`",
+    ),
+    MissingRelease = (
+        "There is a git tag with no corresponding release in the changelog.",
+        "E401",
+        "Tag has no release `{}`",
+    ),
+    TagDateMismatch = (
+        "The release date does not match the date of the release's git tag.",
+        "E402",
+        "Release date does not match tag date `{}`",
+    ),
+    MissingUnreleasedChanges = (
+        "There are commits since the latest release, but the unreleased section has no changes.",
+        "E403",
+        "Missing unreleased changes for commits since `{}`",
+    ),
+    ModifiedRelease = (
+        "A released section has changed. Released notes should not change after release.",
+        "E404",
+        "Release `{}` has changed",
+    ),
+    RemovedRelease = (
+        "A released section has been removed. Released notes should not be removed after release.",
+        "E405",
+        "Release `{}` has been removed",
+    ),
 }
 
 impl TryFrom<String> for Rule {
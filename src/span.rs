@@ -4,11 +4,13 @@ use std::ops::{Deref, Range};
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
+mod encoding;
 mod iter;
 mod locator;
 mod ranged;
 mod unist;
 
+pub use encoding::Encoding;
 pub(crate) use iter::SpanIterator;
 pub use locator::Locator;
 pub use ranged::Ranged;
@@ -65,6 +67,17 @@ impl Span {
             end: offset + self.end,
         }
     }
+
+    /// Return `true` if this span overlaps `other`.
+    ///
+    /// An empty span overlaps a span that contains its offset, including the end offset.
+    pub fn overlaps(&self, other: &Span) -> bool {
+        if self.start == self.end || other.start == other.end {
+            self.start <= other.end && other.start <= self.end
+        } else {
+            self.start < other.end && other.start < self.end
+        }
+    }
 }
 
 impl From<Range<usize>> for Span {
@@ -92,9 +105,22 @@ mod tests {
         assert_eq!(span.offset(5), Span::new(5, 15));
     }
 
+    #[test]
+    fn test_overlaps() {
+        let span = Span::new(5, 10);
+        assert!(span.overlaps(&Span::new(0, 6)));
+        assert!(span.overlaps(&Span::new(9, 20)));
+        assert!(span.overlaps(&Span::new(6, 7)));
+        assert!(!span.overlaps(&Span::new(0, 5)));
+        assert!(!span.overlaps(&Span::new(10, 15)));
+        assert!(span.overlaps(&Span::new(5, 5)));
+        assert!(span.overlaps(&Span::new(10, 10)));
+        assert!(!span.overlaps(&Span::new(11, 11)));
+    }
+
     #[test]
     fn test_range() {
         let span = Span::new(0, 10);
-        assert_eq!(span.range(), span.into())
+        assert_eq!(span.range(), Range::from(span))
     }
 }
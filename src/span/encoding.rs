@@ -0,0 +1,77 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// The unit of column numbers.
+#[derive(Copy, Clone, Debug, Default, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub enum Encoding {
+    /// UTF-8 code units (bytes).
+    #[default]
+    #[cfg_attr(feature = "serde", serde(rename = "utf-8"))]
+    Utf8,
+    /// UTF-16 code units, as used by the Language Server Protocol and JavaScript.
+    #[cfg_attr(feature = "serde", serde(rename = "utf-16"))]
+    Utf16,
+    /// Unicode scalar values (characters).
+    #[cfg_attr(feature = "serde", serde(rename = "utf-32"))]
+    Utf32,
+}
+
+impl Encoding {
+    /// Return the length of `s` in code units.
+    pub fn len(&self, s: &str) -> usize {
+        match self {
+            Self::Utf8 => s.len(),
+            Self::Utf16 => s.encode_utf16().count(),
+            Self::Utf32 => s.chars().count(),
+        }
+    }
+
+    /// Return the length of `c` in code units.
+    fn char_len(&self, c: char) -> usize {
+        match self {
+            Self::Utf8 => c.len_utf8(),
+            Self::Utf16 => c.len_utf16(),
+            Self::Utf32 => 1,
+        }
+    }
+
+    /// Return the byte offset of the code unit `units` in `s`.
+    ///
+    /// If `units` is in the middle of a character, return the offset of the next character. If
+    /// `units` is past the end of `s`, return the length of `s`.
+    pub fn offset(&self, s: &str, units: usize) -> usize {
+        let mut n = 0;
+        for (i, c) in s.char_indices() {
+            if n >= units {
+                return i;
+            }
+            n += self.char_len(c);
+        }
+        s.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_len() {
+        let s = "Café 😀";
+        assert_eq!(Encoding::Utf8.len(s), 10);
+        assert_eq!(Encoding::Utf16.len(s), 7);
+        assert_eq!(Encoding::Utf32.len(s), 6);
+    }
+
+    #[test]
+    fn test_offset() {
+        let s = "Café 😀!";
+        assert_eq!(Encoding::Utf8.offset(s, 5), 5);
+        assert_eq!(Encoding::Utf16.offset(s, 7), 10);
+        assert_eq!(Encoding::Utf32.offset(s, 6), 10);
+        // The middle of a character.
+        assert_eq!(Encoding::Utf16.offset(s, 6), 10);
+        assert_eq!(Encoding::Utf32.offset(s, 100), s.len());
+    }
+}
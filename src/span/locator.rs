@@ -2,12 +2,15 @@ use std::cmp::Ordering;
 
 use crate::diagnostic::Diagnostic;
 
-use super::{Point, Position, Ranged, Span};
+use super::{Encoding, Point, Position, Ranged, Span};
 
 /// Locates spans in the source document.
+///
+/// Columns count the code units of the locator's [`Encoding`]. By default, columns count bytes.
 pub struct Locator<'a> {
     source: &'a str,
     line_spans: Vec<Span>,
+    encoding: Encoding,
 }
 
 impl<'a> Locator<'a> {
@@ -22,7 +25,21 @@ impl<'a> Locator<'a> {
             // No trailing newline.
             line_spans.push(Span::new(start, source.len()))
         }
-        Self { source, line_spans }
+        Self {
+            source,
+            line_spans,
+            encoding: Encoding::default(),
+        }
+    }
+
+    /// Set the encoding of column numbers.
+    pub fn with_encoding(self, encoding: Encoding) -> Self {
+        Self { encoding, ..self }
+    }
+
+    /// Return the encoding of column numbers.
+    pub fn encoding(&self) -> Encoding {
+        self.encoding
     }
 
     /// Return the unist Point for the given offset.
@@ -45,11 +62,27 @@ impl<'a> Locator<'a> {
                 .saturating_sub(1)
                 .min(self.line_spans.len().saturating_sub(1)),
         };
-        Point::new(
-            i + 1,
-            offset.saturating_sub(self.line_spans[i].start) + 1,
-            offset,
-        )
+        let start = self.line_spans[i].start;
+        let column = match self.source.get(start..offset.max(start)) {
+            Some(s) => self.encoding.len(s),
+            // The offset is not on a character boundary.
+            None => offset.saturating_sub(start),
+        };
+        Point::new(i + 1, column + 1, offset)
+    }
+
+    /// Return the offset of a line and column.
+    ///
+    /// Columns past the end of the line resolve to the end of the line, and lines past the end
+    /// of the source resolve to the end of the source.
+    pub fn offset(&self, line: usize, column: usize) -> usize {
+        match self.line_spans.get(line.saturating_sub(1)) {
+            Some(span) => {
+                let s = &self.source[span.range()];
+                span.start + self.encoding.offset(s, column.saturating_sub(1))
+            }
+            None => self.source.len(),
+        }
     }
 
     /// Locate a diagnostic.
@@ -69,11 +102,19 @@ impl<'a> Locator<'a> {
     }
 
     /// Return the contents of line number `line`.
+    #[cfg(feature = "cli")]
     pub(crate) fn line(&self, line: usize) -> &'a str {
         &self.source[self.line_spans[line.saturating_sub(1)].range()]
     }
 
+    /// Return the span of line number `line`, excluding the line ending.
+    #[cfg(feature = "cli")]
+    pub(crate) fn line_span(&self, line: usize) -> Option<Span> {
+        self.line_spans.get(line.checked_sub(1)?).copied()
+    }
+
     /// Return the number of lines in the source.
+    #[cfg(feature = "cli")]
     pub(crate) fn lines(&self) -> usize {
         self.line_spans.len()
     }
@@ -109,6 +150,31 @@ quux";
     }
 
     #[test]
+    fn test_encoding() {
+        let s = "# Café\n\n* 😀 Foo";
+        let foo = s.find("Foo").unwrap();
+        let span = Span::new(foo, foo + 3);
+        for (encoding, column) in [
+            (Encoding::Utf8, 8),
+            (Encoding::Utf16, 6),
+            (Encoding::Utf32, 5),
+        ] {
+            let locator = Locator::new(s).with_encoding(encoding);
+            let position = locator.position(&span);
+            assert_eq!(position.start, Point::new(3, column, foo));
+            assert_eq!(locator.offset(3, column), foo);
+        }
+        let locator = Locator::new(s).with_encoding(Encoding::Utf16);
+        assert_eq!(
+            locator.position(&Span::new(6, 6)).start,
+            Point::new(1, 7, 6)
+        );
+        assert_eq!(locator.offset(1, 100), 7);
+        assert_eq!(locator.offset(100, 1), s.len());
+    }
+
+    #[test]
+    #[cfg(feature = "cli")]
     fn test_line() {
         let s = "foo bar baz\nfoobar foobaz";
         let locator = Locator::new(s);
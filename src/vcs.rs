@@ -0,0 +1,84 @@
+//! Version control data.
+use crate::date::Date;
+
+/// A version control tag for a release, e.g. a git tag `v1.0.0`.
+///
+/// Use [`Linter::with_tags`](crate::Linter::with_tags) to check releases against tags.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Tag {
+    /// The tag name.
+    pub name: String,
+    /// The release version the tag refers to.
+    pub version: String,
+    /// The date the tag was created.
+    pub date: Option<Date>,
+}
+
+impl Tag {
+    /// Create a new tag.
+    pub fn new<S: Into<String>>(name: S, version: S, date: Option<Date>) -> Self {
+        Self {
+            name: name.into(),
+            version: version.into(),
+            date,
+        }
+    }
+
+    /// Create a tag from its name and a pattern like `v{version}`.
+    ///
+    /// Return `None` if the name does not match the pattern.
+    pub fn from_pattern(name: &str, pattern: &str, date: Option<Date>) -> Option<Self> {
+        let (prefix, suffix) = pattern.split_once("{version}")?;
+        let version = name.strip_prefix(prefix)?.strip_suffix(suffix)?;
+        if version.is_empty() {
+            return None;
+        }
+        Some(Self::new(name, version, date))
+    }
+}
+
+/// Commits since a previous release.
+///
+/// Use [`Linter::with_unreleased_commits`](crate::Linter::with_unreleased_commits) to check that
+/// the unreleased section describes the commits.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Commits {
+    /// The revision the commits are counted from, e.g. the latest release tag.
+    pub since: String,
+    /// The number of commits.
+    pub count: usize,
+}
+
+impl Commits {
+    /// Create a new count of commits.
+    pub fn new<S: Into<String>>(since: S, count: usize) -> Self {
+        Self {
+            since: since.into(),
+            count,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_pattern() {
+        assert_eq!(
+            Tag::from_pattern("v1.0.0", "v{version}", None),
+            Some(Tag::new("v1.0.0", "1.0.0", None))
+        );
+        assert_eq!(
+            Tag::from_pattern("notabene-1.0.0-release", "notabene-{version}-release", None),
+            Some(Tag::new("notabene-1.0.0-release", "1.0.0", None))
+        );
+        assert_eq!(
+            Tag::from_pattern("1.0.0", "{version}", None),
+            Some(Tag::new("1.0.0", "1.0.0", None))
+        );
+        assert_eq!(Tag::from_pattern("1.0.0", "v{version}", None), None);
+        assert_eq!(Tag::from_pattern("v", "v{version}", None), None);
+        assert_eq!(Tag::from_pattern("v1.0.0", "v", None), None);
+    }
+}
@@ -0,0 +1,90 @@
+//! JavaScript API.
+//!
+//! Build the module as a `cdylib` and generate the package with
+//! [`wasm-bindgen`](https://github.com/wasm-bindgen/wasm-bindgen):
+//!
+//! ```sh
+//! cargo rustc --lib --release --crate-type cdylib --target wasm32-unknown-unknown --features wasm
+//! wasm-bindgen --target web --out-dir pkg target/wasm32-unknown-unknown/release/notabene.wasm
+//! ```
+//!
+//! The functions accept and return plain JavaScript objects.
+//!
+//! ```js
+//! import init, { parse, lint } from "./pkg/notabene.js";
+//!
+//! await init();
+//! const changelog = parse(source);
+//! const diagnostics = lint(source, { ignore: ["E100"], columnEncoding: "utf-16" });
+//! ```
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use wasm_bindgen::prelude::*;
+
+use crate::api::{self, LintOptions};
+use crate::span::Encoding;
+
+/// Parse a changelog.
+#[wasm_bindgen]
+pub fn parse(source: &str) -> Result<JsValue, JsError> {
+    to_js(&api::parse_changelog(source))
+}
+
+/// Lint a changelog and return the diagnostics with their positions.
+///
+/// `options` may select or ignore rules, and set the layout, the current date, and the column
+/// encoding, which defaults to UTF-16 to match JavaScript strings.
+#[wasm_bindgen]
+pub fn lint(source: &str, options: JsValue) -> Result<JsValue, JsError> {
+    let options: LintOptions = if options.is_undefined() || options.is_null() {
+        LintOptions::default()
+    } else {
+        from_js(&options)?
+    };
+    to_js(&api::lint_changelog(source, &options, Encoding::Utf16))
+}
+
+/// Convert a value to a JavaScript object through JSON.
+fn to_js<T: Serialize>(value: &T) -> Result<JsValue, JsError> {
+    let json = serde_json::to_string(value)?;
+    js_sys::JSON::parse(&json).map_err(|_| JsError::new("cannot convert value to JavaScript"))
+}
+
+/// Convert a JavaScript object to a value through JSON.
+fn from_js<T: DeserializeOwned>(value: &JsValue) -> Result<T, JsError> {
+    let json = js_sys::JSON::stringify(value)
+        .map_err(|_| JsError::new("cannot convert value from JavaScript"))?;
+    Ok(serde_json::from_str(&String::from(json))?)
+}
+
+#[cfg(all(test, target_arch = "wasm32"))]
+mod tests {
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+
+    #[wasm_bindgen_test]
+    fn test_parse() {
+        let changelog = parse("# Changelog\n").unwrap();
+        let title = js_sys::Reflect::get(&changelog, &"title".into()).unwrap();
+        assert_eq!(title.as_string().as_deref(), Some("Changelog"));
+    }
+
+    #[wasm_bindgen_test]
+    fn test_lint() {
+        let diagnostics = lint("", JsValue::UNDEFINED).unwrap();
+        let diagnostics = js_sys::Array::from(&diagnostics);
+        let code = js_sys::Reflect::get(&diagnostics.get(0), &"code".into()).unwrap();
+        assert_eq!(code.as_string().as_deref(), Some("E001"));
+        assert!(lint("", js_sys::JSON::parse(r#"{"foo": 1}"#).unwrap()).is_err());
+    }
+
+    #[wasm_bindgen_test]
+    fn test_lint_today() {
+        // Without `today`, the current date comes from JavaScript.
+        let source = "# Changelog\n\n## [1.0.0] - 9999-01-01\n\n### Added\n\n* Foo\n\n[1.0.0]: https://example.org/\n";
+        let options = js_sys::JSON::parse(r#"{"select": ["E205"]}"#).unwrap();
+        let diagnostics = js_sys::Array::from(&lint(source, options).unwrap());
+        assert_eq!(diagnostics.length(), 1);
+    }
+}